@@ -0,0 +1,113 @@
+use std::{
+    cell::RefCell, collections::HashMap, env, fs, io, path::PathBuf, rc::Rc, thread::LocalKey,
+    time::SystemTime,
+};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Reads a setting from the env var or, if it is not set, from the
+/// `[package.metadata.web_proc_macros]` table of the crate being compiled.
+pub fn setting(env_var: &str, key: &str) -> Option<String> {
+    env::var(env_var).ok().or_else(|| metadata_value(key))
+}
+
+/// Tokens which make the compiler track the env vars of the settings read by a
+/// macro and the manifest, so the macro runs again when any of them changes.
+pub fn setting_tracking(env_vars: &[&str]) -> TokenStream {
+    let manifest = manifest_dir().join("Cargo.toml");
+    let manifest_tracking = manifest.is_file().then(|| {
+        let manifest = manifest.to_string_lossy().into_owned();
+        quote!(
+            const _: &[u8] = include_bytes!(#manifest);
+        )
+    });
+
+    quote! {
+        #(const _: Option<&str> = option_env!(#env_vars);)*
+        #manifest_tracking
+    }
+}
+
+pub fn manifest_dir() -> PathBuf {
+    env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
+/// Parsed files by path, with the modification time they were parsed at.
+pub type FileCache<T> = RefCell<HashMap<PathBuf, (SystemTime, Rc<T>)>>;
+
+/// Parses a file once per change, the macros of a crate share the parsed file.
+pub fn load_cached<T>(
+    cache: &'static LocalKey<FileCache<T>>,
+    path: &PathBuf,
+    parse: impl FnOnce(&str) -> T,
+) -> io::Result<Rc<T>> {
+    let modified = fs::metadata(path)?.modified()?;
+    if let Some(parsed) = cache.with(|cache| {
+        cache
+            .borrow()
+            .get(path)
+            .filter(|(cached, _)| *cached == modified)
+            .map(|(_, parsed)| parsed.clone())
+    }) {
+        return Ok(parsed);
+    }
+
+    let parsed = Rc::new(parse(&fs::read_to_string(path)?));
+    cache.with(|cache| {
+        cache
+            .borrow_mut()
+            .insert(path.clone(), (modified, parsed.clone()))
+    });
+
+    Ok(parsed)
+}
+
+thread_local! {
+    static MANIFESTS: FileCache<Metadata> = RefCell::new(HashMap::new());
+}
+
+/// The `key = "value"` settings of `[package.metadata.web_proc_macros]`.
+type Metadata = Vec<(String, String)>;
+
+/// Reads the manifest once per change, every stmt macro looks up its settings.
+fn metadata_value(key: &str) -> Option<String> {
+    let path = manifest_dir().join("Cargo.toml");
+    let metadata = load_cached(&MANIFESTS, &path, parse_metadata).ok()?;
+
+    metadata
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.clone())
+}
+
+/// Just supports `key = "value"` lines, which is all the settings need.
+fn parse_metadata(manifest: &str) -> Metadata {
+    let mut metadata = Vec::new();
+    let mut in_section = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line == "[package.metadata.web_proc_macros]";
+            continue;
+        }
+
+        if !in_section {
+            continue;
+        }
+
+        if let Some((name, value)) = line.split_once('=') {
+            let value = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|value| value.split_once('"'));
+            if let Some((value, _)) = value {
+                metadata.push((name.trim().to_string(), value.to_string()));
+            }
+        }
+    }
+
+    metadata
+}
@@ -0,0 +1,282 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Fields, GenericArgument, Lit, Meta,
+    NestedMeta, PathArguments, Type,
+};
+
+use crate::naming::to_snake_case;
+use crate::tenant::{tenant_column, tenant_tracking};
+
+#[derive(Clone, Copy)]
+enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl Dialect {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "mysql" => Dialect::MySql,
+            "postgres" | "postgresql" => Dialect::Postgres,
+            "sqlite" => Dialect::Sqlite,
+            _ => panic!("Unknown sql dialect `{name}`, expected `mysql`, `postgres` or `sqlite`"),
+        }
+    }
+
+    fn column_type(self, rust_ty: &str) -> Option<&'static str> {
+        let ty = match (self, rust_ty) {
+            (Dialect::MySql, "String" | "str") => "VARCHAR(255)",
+            (Dialect::MySql, "bool") => "BOOLEAN",
+            (Dialect::MySql, "i8") => "TINYINT",
+            (Dialect::MySql, "u8") => "TINYINT UNSIGNED",
+            (Dialect::MySql, "i16") => "SMALLINT",
+            (Dialect::MySql, "u16") => "SMALLINT UNSIGNED",
+            (Dialect::MySql, "i32") => "INT",
+            (Dialect::MySql, "u32") => "INT UNSIGNED",
+            (Dialect::MySql, "i64") => "BIGINT",
+            (Dialect::MySql, "u64") => "BIGINT UNSIGNED",
+            (Dialect::MySql, "f32") => "FLOAT",
+            (Dialect::MySql, "f64") => "DOUBLE",
+            (Dialect::MySql, "Vec<u8>") => "BLOB",
+
+            (Dialect::Postgres, "String" | "str") => "TEXT",
+            (Dialect::Postgres, "bool") => "BOOLEAN",
+            (Dialect::Postgres, "i8" | "u8" | "i16") => "SMALLINT",
+            (Dialect::Postgres, "u16" | "i32") => "INTEGER",
+            (Dialect::Postgres, "u32" | "i64") => "BIGINT",
+            (Dialect::Postgres, "u64") => "NUMERIC(20)",
+            (Dialect::Postgres, "f32") => "REAL",
+            (Dialect::Postgres, "f64") => "DOUBLE PRECISION",
+            (Dialect::Postgres, "Vec<u8>") => "BYTEA",
+
+            (Dialect::Sqlite, "String" | "str") => "TEXT",
+            (
+                Dialect::Sqlite,
+                "bool" | "i8" | "u8" | "i16" | "u16" | "i32" | "u32" | "i64" | "u64",
+            ) => "INTEGER",
+            (Dialect::Sqlite, "f32" | "f64") => "REAL",
+            (Dialect::Sqlite, "Vec<u8>") => "BLOB",
+
+            _ => return None,
+        };
+
+        Some(ty)
+    }
+}
+
+#[derive(Default)]
+struct ColumnAttr {
+    ty: Option<String>,
+    primary_key: bool,
+    unique: bool,
+    index: bool,
+    skip: bool,
+}
+
+impl ColumnAttr {
+    fn from_attrs(attrs: &[Attribute]) -> Self {
+        let mut column = ColumnAttr::default();
+        for meta in sql_metas(attrs) {
+            match meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("primary_key") => {
+                    column.primary_key = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("unique") => {
+                    column.unique = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("index") => {
+                    column.index = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => column.skip = true,
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("ty") => {
+                    column.ty = Some(lit_string(&nv.lit))
+                }
+                _ => panic!(
+                    "Invalid value for #[sql], expected `ty`, `primary_key`, `unique`, `index` or `skip`"
+                ),
+            }
+        }
+
+        column
+    }
+}
+
+pub fn create_table_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut table = to_snake_case(&name.to_string());
+    let mut dialect = Dialect::MySql;
+    let mut scoped = None;
+    let mut tenant = None;
+    let mut tenant_ty = None;
+    for meta in sql_metas(&input.attrs) {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("table") => {
+                table = lit_string(&nv.lit)
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("dialect") => {
+                dialect = Dialect::from_name(&lit_string(&nv.lit))
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("tenant") => scoped = Some(true),
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("tenant") => {
+                scoped = Some(true);
+                tenant = Some(lit_string(&nv.lit));
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("unscoped") => {
+                scoped = Some(false)
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("tenant_ty") => {
+                tenant_ty = Some(lit_string(&nv.lit))
+            }
+            _ => panic!(
+                "Invalid value for #[sql], expected `table`, `dialect`, `tenant`, `tenant_ty` or `unscoped`"
+            ),
+        }
+    }
+    let tenant = tenant_column(scoped, tenant);
+
+    let fields = if let Data::Struct(data_struct) = &input.data {
+        &data_struct.fields
+    } else {
+        panic!("CreateTable only supports structs with named fields");
+    };
+
+    let fields = if let Fields::Named(f) = fields {
+        &f.named
+    } else {
+        panic!("CreateTable only supports structs with named fields");
+    };
+
+    let mut columns = Vec::new();
+    let mut primary_keys = Vec::new();
+    let mut indexes = Vec::new();
+    let mut indexed_columns = Vec::new();
+    let mut column_names = Vec::new();
+    for field in fields {
+        let attr = ColumnAttr::from_attrs(&field.attrs);
+        if attr.skip {
+            continue;
+        }
+
+        let column = field.ident.as_ref().unwrap().to_string();
+        let (rust_ty, nullable) = rust_type_name(&field.ty);
+        let sql_ty = match attr.ty {
+            Some(ty) => ty,
+            None => rust_ty
+                .as_deref()
+                .and_then(|ty| dialect.column_type(ty))
+                .unwrap_or_else(|| {
+                    panic!("Unsupported type for column `{column}`, use #[sql(ty = \"...\")]")
+                })
+                .to_string(),
+        };
+
+        let mut definition = format!("{column} {sql_ty}");
+        if !nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if attr.unique {
+            definition.push_str(" UNIQUE");
+        }
+        columns.push(definition);
+        column_names.push(column.clone());
+
+        if attr.primary_key {
+            primary_keys.push(column.clone());
+        }
+        if attr.index {
+            indexes.push(format!(
+                "CREATE INDEX {table}_{column}_idx ON {table} ({column});"
+            ));
+        }
+        if attr.index || attr.unique {
+            indexed_columns.push(column);
+        }
+    }
+
+    if let Some(tenant) = &tenant {
+        if !column_names.contains(tenant) {
+            let ty = tenant_ty
+                .as_deref()
+                .or_else(|| dialect.column_type("String"))
+                .unwrap();
+            columns.push(format!("{tenant} {ty} NOT NULL"));
+        }
+
+        if !indexed_columns.contains(tenant) && primary_keys.first() != Some(tenant) {
+            indexes.push(format!(
+                "CREATE INDEX {table}_{tenant}_idx ON {table} ({tenant});"
+            ));
+        }
+    }
+
+    if !primary_keys.is_empty() {
+        columns.push(format!("PRIMARY KEY ({})", primary_keys.join(", ")));
+    }
+
+    let mut statements = vec![format!("CREATE TABLE {table} ({});", columns.join(", "))];
+    statements.extend(indexes);
+    let sql = statements.join("\n");
+
+    let tenant_tracking = tenant_tracking();
+    let expanded = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub const CREATE_TABLE_SQL: &'static str = #sql;
+        }
+
+        #tenant_tracking
+    };
+
+    TokenStream::from(expanded)
+}
+
+fn sql_metas(attrs: &[Attribute]) -> Vec<NestedMeta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("sql"))
+        .flat_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested,
+            _ => panic!("Error parsing #[sql] attribute"),
+        })
+        .collect()
+}
+
+fn lit_string(lit: &Lit) -> String {
+    if let Lit::Str(s) = lit {
+        s.value()
+    } else {
+        panic!("#[sql] values must be string literals");
+    }
+}
+
+/// Returns the name used to look up the column type and if the column is nullable.
+fn rust_type_name(ty: &Type) -> (Option<String>, bool) {
+    match ty {
+        Type::Reference(reference) => (rust_type_name(&reference.elem).0, false),
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last().unwrap();
+            let inner = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => match args.args.first() {
+                    Some(GenericArgument::Type(inner)) => Some(inner),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            match (segment.ident.to_string().as_str(), inner) {
+                ("Option", Some(inner)) => (rust_type_name(inner).0, true),
+                ("Vec", Some(inner)) => {
+                    let inner = rust_type_name(inner).0;
+                    (inner.map(|inner| format!("Vec<{inner}>")), false)
+                }
+                (ident, None) => (Some(ident.to_string()), false),
+                _ => (None, false),
+            }
+        }
+        _ => (None, false),
+    }
+}
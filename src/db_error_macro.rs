@@ -0,0 +1,104 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident};
+
+use crate::error_kind_attr::{attr_args, AttrArg};
+
+/// Driver error codes of a variant, from its `#[db_error(...)]` attributes.
+struct DbCodes {
+    mysql: Vec<u16>,
+    sqlstate: Vec<String>,
+}
+
+impl DbCodes {
+    fn from_variant(variant: &syn::Variant) -> Self {
+        let mut codes = DbCodes {
+            mysql: Vec::new(),
+            sqlstate: Vec::new(),
+        };
+        for arg in attr_args(&variant.attrs, "db_error").into_iter().flatten() {
+            match arg {
+                AttrArg::Value(path, value) if path.is_ident("mysql") => {
+                    codes.mysql.push(value.lit_int("mysql"));
+                }
+                AttrArg::Value(path, value) if path.is_ident("sqlstate") => {
+                    let sqlstate = value.lit_str("sqlstate");
+                    if sqlstate.len() != 5 {
+                        panic!("SQLSTATE `{sqlstate}` must have 5 characters");
+                    }
+                    codes.sqlstate.push(sqlstate);
+                }
+                _ => panic!("Invalid value for #[db_error], expected `mysql` or `sqlstate`"),
+            }
+        }
+
+        codes
+    }
+}
+
+pub fn db_error_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let variants = if let Data::Enum(data) = &input.data {
+        &data.variants
+    } else {
+        panic!("DbError just can be used in enums");
+    };
+
+    let mut mysql_arms = Vec::new();
+    let mut sqlstate_arms = Vec::new();
+    let mut mysql_codes: Vec<(u16, &Ident)> = Vec::new();
+    let mut sqlstates: Vec<(String, &Ident)> = Vec::new();
+    for variant in variants {
+        let codes = DbCodes::from_variant(variant);
+        if codes.mysql.is_empty() && codes.sqlstate.is_empty() {
+            continue;
+        }
+
+        let ident = &variant.ident;
+        if !matches!(variant.fields, Fields::Unit) {
+            panic!("#[db_error] can only be used in unit variants, `{ident}` has fields");
+        }
+
+        for code in codes.mysql {
+            if let Some((_, other)) = mysql_codes.iter().find(|(other, _)| *other == code) {
+                panic!("`{ident}` and `{other}` both use the mysql code {code}");
+            }
+            mysql_codes.push((code, ident));
+            mysql_arms.push(quote! {
+                (#code, _) => Some(Self::#ident),
+            });
+        }
+        for sqlstate in codes.sqlstate {
+            if let Some((_, other)) = sqlstates.iter().find(|(other, _)| *other == sqlstate) {
+                panic!("`{ident}` and `{other}` both use the SQLSTATE {sqlstate}");
+            }
+            sqlstate_arms.push(quote! {
+                (_, Some(#sqlstate)) => Some(Self::#ident),
+            });
+            sqlstates.push((sqlstate, ident));
+        }
+    }
+
+    if mysql_arms.is_empty() && sqlstate_arms.is_empty() {
+        panic!("DbError requires a variant with #[db_error(mysql = CODE)] or #[db_error(sqlstate = \"CODE\")]");
+    }
+
+    // The mysql codes are more specific than the SQLSTATE, which groups several of them.
+    let expanded = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the variant of a database error from its mysql code or SQLSTATE.
+            pub fn from_db_code(code: u16, sqlstate: Option<&str>) -> Option<Self> {
+                match (code, sqlstate) {
+                    #(#mysql_arms)*
+                    #(#sqlstate_arms)*
+                    _ => None,
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -5,19 +5,29 @@ use syn::{
     parse_macro_input, Expr, Result,
 };
 
+use crate::{
+    config::setting_tracking,
+    location_comment::with_location,
+    schema::{check_columns, SCHEMA_VAR},
+    tenant::{TenantScope, TENANT_COLUMN_VAR},
+};
+
 struct DeleteQueryInput {
+    tenant: TenantScope,
     table_name: Expr,
     where_clause: Expr,
 }
 
 impl Parse for DeleteQueryInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        let tenant = TenantScope::parse(input)?;
         let table_name = input.parse()?;
         input.parse::<syn::Token![,]>()?;
 
         let where_clause = input.parse()?;
 
         Ok(DeleteQueryInput {
+            tenant,
             table_name,
             where_clause,
         })
@@ -27,7 +37,12 @@ impl Parse for DeleteQueryInput {
 pub fn delete_stmt_macro(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeleteQueryInput);
     let table_name = &input.table_name;
-    let where_clause = &input.where_clause;
+    let where_clause = input.tenant.scope_where(Some(&input.where_clause));
+
+    let schema_tracking = match check_columns(table_name, &[]) {
+        Ok(tracking) => tracking,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     let expanded = quote! {
         format!(
@@ -37,5 +52,14 @@ pub fn delete_stmt_macro(input: TokenStream) -> TokenStream {
         )
     };
 
-    TokenStream::from(expanded)
+    let expanded = with_location(expanded);
+    let settings_tracking = setting_tracking(&[SCHEMA_VAR, TENANT_COLUMN_VAR]);
+
+    TokenStream::from(quote! {
+        {
+            #settings_tracking
+            #schema_tracking
+            #expanded
+        }
+    })
 }
@@ -0,0 +1,98 @@
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token, Attribute, Lit, Path, Result, Token,
+};
+
+/// Argument of an `#[error_kind(...)]` attribute. Unlike `syn::Meta`, the
+/// values can be paths, e.g. `default = Internal` or `map = kinds::from_db`.
+pub enum AttrArg {
+    /// `transparent`, `ErrorType`
+    Flag(Path),
+    /// `status = 404`, `default = Internal`
+    Value(Path, AttrValue),
+    /// `map(From)`
+    List(Path, Vec<AttrArg>),
+}
+
+pub enum AttrValue {
+    Lit(Lit),
+    Path(Path),
+}
+
+impl Parse for AttrArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.call(Path::parse_mod_style)?;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            let value = if input.peek(Lit) {
+                AttrValue::Lit(input.parse()?)
+            } else {
+                AttrValue::Path(input.parse()?)
+            };
+            Ok(AttrArg::Value(path, value))
+        } else if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let args = Punctuated::<AttrArg, Token![,]>::parse_terminated(&content)?;
+            Ok(AttrArg::List(path, args.into_iter().collect()))
+        } else {
+            Ok(AttrArg::Flag(path))
+        }
+    }
+}
+
+impl AttrArg {
+    pub fn is_flag(&self, name: &str) -> bool {
+        matches!(self, AttrArg::Flag(path) if path.is_ident(name))
+    }
+}
+
+impl AttrValue {
+    pub fn lit_str(&self, name: &str) -> String {
+        match self {
+            AttrValue::Lit(Lit::Str(lit)) => lit.value(),
+            _ => panic!("`{name}` must be a string literal"),
+        }
+    }
+
+    pub fn lit_int(&self, name: &str) -> u16 {
+        match self {
+            AttrValue::Lit(Lit::Int(lit)) => lit
+                .base10_parse()
+                .unwrap_or_else(|_| panic!("`{name}` must be a valid integer")),
+            _ => panic!("`{name}` must be an integer"),
+        }
+    }
+
+    /// Accepts `name = Path` and `name = "Path"`.
+    pub fn path(&self, name: &str) -> Path {
+        match self {
+            AttrValue::Path(path) => path.clone(),
+            AttrValue::Lit(Lit::Str(lit)) => lit
+                .parse()
+                .unwrap_or_else(|_| panic!("`{name}` must be a path")),
+            _ => panic!("`{name}` must be a path"),
+        }
+    }
+}
+
+/// Arguments of each `#[error_kind(...)]` attribute.
+pub fn error_kind_args(attrs: &[Attribute]) -> Vec<Vec<AttrArg>> {
+    attr_args(attrs, "error_kind")
+}
+
+/// Arguments of each `#[NAME(...)]` attribute.
+pub fn attr_args(attrs: &[Attribute], name: &str) -> Vec<Vec<AttrArg>> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(name))
+        .map(|attr| {
+            attr.parse_args_with(Punctuated::<AttrArg, Token![,]>::parse_terminated)
+                .unwrap_or_else(|err| panic!("Error parsing #[{name}]: {err}"))
+                .into_iter()
+                .collect()
+        })
+        .collect()
+}
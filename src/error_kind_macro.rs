@@ -1,109 +1,2089 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Meta, NestedMeta, Path};
+use proc_macro2::{Ident, Span, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    ext::IdentExt, parse_macro_input, parse_quote, spanned::Spanned, DeriveInput, Field, Fields,
+    GenericArgument, Generics, LitStr, Member, Path, PathArguments, Type, Variant, Visibility,
+};
 
-use crate::partial_struct::find_attribute;
+use crate::error_kind_attr::{error_kind_args, AttrArg, AttrValue};
+use crate::naming::to_snake_case;
+
+/// HTTP status code set with `status = CODE`.
+fn status_code(value: &AttrValue) -> u16 {
+    let status = value.lit_int("status");
+    if !(100..=599).contains(&status) {
+        panic!("`status = {status}` isn't an HTTP status code, expected 100 to 599");
+    }
+
+    status
+}
+
+/// A classification of the errors, e.g. `#[error_kind(Severity, accessor = severity)]`,
+/// which generates its own accessor.
+struct Dimension {
+    /// Taken from the variants by `ImplKind` when the enum doesn't set it.
+    ty: Option<Path>,
+    /// `kind` for the first dimension, the snake case type name for the others.
+    accessor: Option<Ident>,
+    /// Kind variant of the variants without attribute.
+    default: Option<Path>,
+    kind_trait: Option<Path>,
+    /// Checks that the variants use every kind, except the ones in `allow_unused(...)`.
+    exhaustive: bool,
+    allow_unused: Vec<Path>,
+}
+
+impl Dimension {
+    fn new(ty: Option<Path>) -> Self {
+        Dimension {
+            ty,
+            accessor: None,
+            default: None,
+            kind_trait: None,
+            exhaustive: false,
+            allow_unused: Vec::new(),
+        }
+    }
+
+    fn ty(&self) -> &Path {
+        self.ty.as_ref().unwrap()
+    }
+
+    fn accessor(&self) -> &Ident {
+        self.accessor.as_ref().unwrap()
+    }
+}
+
+/// Options of the `#[error_kind(...)]` attributes of the enum.
+struct EnumAttr {
+    /// The first one is the kind, required by `ErrorKind`.
+    dimensions: Vec<Dimension>,
+    status: Option<u16>,
+    /// Prefix of the codes taken from the variant names, set with `code` or `code_prefix`.
+    code_prefix: Option<String>,
+    /// Generates `grpc_code`, set with `grpc`, `grpc = ...` or `grpc(...)`.
+    grpc_code: bool,
+    /// Default gRPC code, `grpc = Internal`.
+    grpc: Option<Path>,
+    /// gRPC code of each kind variant, `grpc(NotFound = NotFound, Conflict = AlreadyExists)`.
+    grpc_table: Vec<(Path, Path)>,
+    /// Default log level, `level = error`.
+    level: Option<String>,
+    /// Generates `metric_label`, set with `metric` or a default `metric = "..."`.
+    metric_label: bool,
+    metric: Option<String>,
+    impl_error: bool,
+    /// Generates a snake case constructor for each variant.
+    constructors: bool,
+    /// Generates `VARIANT_KINDS` and `kind_of_variant`.
+    variant_kinds: bool,
+    /// Generates `is_retryable`, `is_client_error` and `is_server_error` from the status.
+    classification: bool,
+    /// Base URI of the problem types, set with `problem_details(base_uri = "...")`.
+    problem_details: Option<String>,
+    /// Name of the kind enum generated from the kinds used in the variants.
+    generate: Option<Ident>,
+}
+
+impl EnumAttr {
+    fn from_input(input: &DeriveInput) -> Self {
+        let mut attr = EnumAttr {
+            dimensions: Vec::new(),
+            status: None,
+            code_prefix: None,
+            grpc_code: false,
+            grpc: None,
+            grpc_table: Vec::new(),
+            level: None,
+            metric_label: false,
+            metric: None,
+            impl_error: false,
+            constructors: false,
+            variant_kinds: false,
+            classification: false,
+            problem_details: None,
+            generate: None,
+        };
+        for args in error_kind_args(&input.attrs) {
+            // Options of an attribute without type are for the kind.
+            let mut dimension = 0;
+            for (i, arg) in args.into_iter().enumerate() {
+                match arg {
+                    AttrArg::Flag(path) if path.is_ident("impl_error") => attr.impl_error = true,
+                    AttrArg::Flag(path) if path.is_ident("constructors") => {
+                        attr.constructors = true;
+                    }
+                    AttrArg::Flag(path) if path.is_ident("variant_kinds") => {
+                        attr.variant_kinds = true;
+                    }
+                    AttrArg::Flag(path) if path.is_ident("classification") => {
+                        attr.classification = true;
+                    }
+                    AttrArg::Flag(path) if path.is_ident("code") => {
+                        attr.code_prefix = Some(String::new());
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("code_prefix") => {
+                        attr.code_prefix = Some(value.lit_str("code_prefix"));
+                    }
+                    AttrArg::Flag(path) if path.is_ident("grpc") => attr.grpc_code = true,
+                    AttrArg::Value(path, value) if path.is_ident("level") => {
+                        attr.level = Some(log_level(&value.path("level")));
+                    }
+                    AttrArg::Flag(path) if path.is_ident("metric") => attr.metric_label = true,
+                    AttrArg::Value(path, value) if path.is_ident("metric") => {
+                        attr.metric_label = true;
+                        attr.metric = Some(value.lit_str("metric"));
+                    }
+                    AttrArg::Flag(path) if path.is_ident("exhaustive") => {
+                        attr.dimension(dimension).exhaustive = true;
+                    }
+                    AttrArg::List(path, args) if path.is_ident("allow_unused") => {
+                        let allow_unused = args.into_iter().map(|arg| match arg {
+                            AttrArg::Flag(kind) => kind,
+                            _ => panic!("Invalid value for allow_unused, expected kind variants"),
+                        });
+                        attr.dimension(dimension).allow_unused.extend(allow_unused);
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("grpc") => {
+                        attr.grpc_code = true;
+                        attr.grpc = Some(value.path("grpc"));
+                    }
+                    AttrArg::List(path, args) if path.is_ident("grpc") => {
+                        attr.grpc_code = true;
+                        for arg in args {
+                            match arg {
+                                AttrArg::Value(kind, value) => {
+                                    attr.grpc_table.push((kind, value.path("grpc")));
+                                }
+                                _ => panic!("Invalid value for grpc, expected `KIND = CODE`"),
+                            }
+                        }
+                    }
+                    AttrArg::Flag(path) if path.is_ident("problem_details") => {
+                        attr.problem_details = Some(String::new());
+                    }
+                    AttrArg::List(path, args) if path.is_ident("problem_details") => {
+                        let base_uri = args.into_iter().map(|arg| match arg {
+                            AttrArg::Value(path, value) if path.is_ident("base_uri") => {
+                                value.lit_str("base_uri")
+                            }
+                            _ => panic!("Invalid value for problem_details, expected `base_uri`"),
+                        });
+                        attr.problem_details = Some(base_uri.collect());
+                    }
+                    AttrArg::Flag(path) if i == 0 => dimension = attr.add_dimension(path),
+                    AttrArg::Value(path, value) if path.is_ident("accessor") => {
+                        let accessor = value.path("accessor");
+                        let accessor = accessor
+                            .get_ident()
+                            .expect("`accessor` must be an identifier");
+                        attr.dimension(dimension).accessor = Some(accessor.clone());
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("kind_trait") => {
+                        attr.dimension(dimension).kind_trait = Some(value.path("kind_trait"));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("default") => {
+                        attr.dimension(dimension).default = Some(value.path("default"));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("status") => {
+                        attr.status = Some(status_code(&value));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("generate") => {
+                        let generate = value.path("generate");
+                        let ident = generate
+                            .get_ident()
+                            .expect("`generate` must be an identifier")
+                            .clone();
+                        attr.dimension(0).ty = Some(generate);
+                        attr.generate = Some(ident);
+                    }
+                    _ => panic!(
+                        "Invalid value for #[error_kind], expected `accessor`, `default`, `generate`, `kind_trait`, `status`, `code`, `code_prefix`, `grpc`, `level`, `metric`, `exhaustive`, `allow_unused`, `impl_error`, `constructors`, `variant_kinds`, `classification` or `problem_details`"
+                    ),
+                }
+            }
+        }
+
+        attr
+    }
+
+    fn dimension(&mut self, index: usize) -> &mut Dimension {
+        if self.dimensions.is_empty() {
+            self.dimensions.push(Dimension::new(None));
+        }
+
+        &mut self.dimensions[index]
+    }
+
+    /// Adds the dimension of the type and returns its index. The kind can be
+    /// declared by an attribute without type, e.g. `#[error_kind(default = ...)]`.
+    fn add_dimension(&mut self, ty: Path) -> usize {
+        if self.find_dimension(&ty).is_some() {
+            panic!(
+                "`{}` is declared twice in #[error_kind]",
+                ty.to_token_stream()
+            );
+        }
+
+        match self.dimensions.first_mut() {
+            Some(kind) if kind.ty.is_none() => {
+                kind.ty = Some(ty);
+                0
+            }
+            _ => {
+                self.dimensions.push(Dimension::new(Some(ty)));
+                self.dimensions.len() - 1
+            }
+        }
+    }
+
+    fn find_dimension(&self, ty: &Path) -> Option<usize> {
+        self.dimensions.iter().position(|dimension| {
+            dimension
+                .ty
+                .as_ref()
+                .is_some_and(|other| same_path(other, ty))
+        })
+    }
+
+    /// First option which only `ErrorKind` handles.
+    fn error_kind_option(&self) -> Option<&'static str> {
+        let options = [
+            (self.status.is_some(), "status"),
+            (self.code_prefix.is_some(), "code"),
+            (self.grpc_code, "grpc"),
+            (self.level.is_some(), "level"),
+            (self.metric_label, "metric"),
+            (self.impl_error, "impl_error"),
+            (self.constructors, "constructors"),
+            (self.variant_kinds, "variant_kinds"),
+            (self.classification, "classification"),
+            (self.problem_details.is_some(), "problem_details"),
+            (self.generate.is_some(), "generate"),
+        ];
+        options
+            .iter()
+            .find(|(set, _)| *set)
+            .map(|(_, option)| *option)
+    }
+
+    /// Completes the dimensions with the kind types of the variants. `ErrorKind`
+    /// requires them in the enum, `ImplKind` adds them in order of appearance.
+    fn resolve_dimensions(&mut self, variants: &[ErrorVariant], derive: Derive) {
+        for (kind_ty, _) in variants.iter().flat_map(|variant| &variant.attr.kinds) {
+            if self.find_dimension(kind_ty).is_some() {
+                continue;
+            }
+
+            if derive == Derive::ErrorKind {
+                panic!(
+                    "`{}` is not a kind of the enum, add #[error_kind({0})] to the enum",
+                    kind_ty.to_token_stream()
+                );
+            }
+            self.add_dimension(kind_ty.clone());
+        }
+
+        if !matches!(self.dimensions.first(), Some(kind) if kind.ty.is_some()) {
+            panic!("The kind type requires a variant with #[error_kind(KIND_IDENT, VARIANT)]");
+        }
+
+        let mut accessors = Vec::new();
+        for (i, dimension) in self.dimensions.iter_mut().enumerate() {
+            let accessor = dimension.accessor.get_or_insert_with(|| {
+                if i == 0 {
+                    return Ident::new("kind", Span::call_site());
+                }
+
+                let ty = dimension.ty.as_ref().unwrap();
+                let name = ty.segments.last().unwrap().ident.to_string();
+                Ident::new(&to_snake_case(&name), Span::call_site())
+            });
+            if accessors.contains(accessor) {
+                panic!("Several kinds use the accessor `{accessor}`, set another with `accessor = ...`");
+            }
+            accessors.push(accessor.clone());
+        }
+    }
+}
+
+/// Conversion of the kind of the wrapped error of a transparent variant.
+enum KindMap {
+    /// `map = path::to::fn`
+    Fn(Path),
+    /// `map(From)`
+    From,
+}
+
+/// Options of the `#[error_kind(...)]` attributes of a variant.
+struct VariantAttr {
+    /// Kind type and kind variant of each dimension set in the variant.
+    kinds: Vec<(Path, Path)>,
+    /// The dimensions not set are taken from the wrapped error.
+    transparent: bool,
+    status: Option<u16>,
+    code: Option<String>,
+    grpc: Option<Path>,
+    level: Option<String>,
+    metric: Option<String>,
+    message: Option<String>,
+    retryable: bool,
+    client_error: bool,
+    /// Generates `From` for the wrapped error of a transparent variant.
+    from: bool,
+    map: Option<KindMap>,
+}
+
+impl VariantAttr {
+    /// First option which only `ErrorKind` handles.
+    fn error_kind_option(&self) -> Option<&'static str> {
+        let options = [
+            (self.status.is_some(), "status"),
+            (self.code.is_some(), "code"),
+            (self.grpc.is_some(), "grpc"),
+            (self.level.is_some(), "level"),
+            (self.metric.is_some(), "metric"),
+            (self.message.is_some(), "message"),
+            (self.retryable, "retryable"),
+            (self.client_error, "client_error"),
+            (self.from, "from"),
+        ];
+        options
+            .iter()
+            .find(|(set, _)| *set)
+            .map(|(_, option)| *option)
+    }
+
+    fn from_variant(variant: &Variant, enum_attr: &EnumAttr) -> Self {
+        let mut attr = VariantAttr {
+            kinds: Vec::new(),
+            transparent: false,
+            status: None,
+            code: None,
+            grpc: None,
+            level: None,
+            metric: None,
+            message: None,
+            retryable: false,
+            client_error: false,
+            from: false,
+            map: None,
+        };
+        for args in error_kind_args(&variant.attrs) {
+            let mut paths = Vec::new();
+            let mut transparent = false;
+            for arg in args {
+                match arg {
+                    AttrArg::Flag(path) if path.is_ident("transparent") => transparent = true,
+                    AttrArg::Flag(path) if path.is_ident("from") => attr.from = true,
+                    AttrArg::Flag(path) if path.is_ident("retryable") => attr.retryable = true,
+                    AttrArg::Flag(path) if path.is_ident("client_error") => {
+                        attr.client_error = true;
+                    }
+                    AttrArg::Flag(path) => paths.push(path),
+                    AttrArg::Value(path, value) if path.is_ident("status") => {
+                        attr.status = Some(status_code(&value));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("code") => {
+                        attr.code = Some(value.lit_str("code"));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("grpc") => {
+                        attr.grpc = Some(value.path("grpc"));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("level") => {
+                        attr.level = Some(log_level(&value.path("level")));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("metric") => {
+                        attr.metric = Some(value.lit_str("metric"));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("message") => {
+                        attr.message = Some(value.lit_str("message"));
+                    }
+                    AttrArg::Value(path, value) if path.is_ident("map") => {
+                        attr.map = Some(KindMap::Fn(value.path("map")));
+                    }
+                    AttrArg::List(path, args)
+                        if path.is_ident("map") && args.len() == 1 && args[0].is_flag("From") =>
+                    {
+                        attr.map = Some(KindMap::From);
+                    }
+                    _ => panic!("Invalid value for #[error_kind]"),
+                }
+            }
+
+            let kind = match (transparent, paths.len(), &enum_attr.generate) {
+                (false, 2, _) => (paths[0].clone(), paths[1].clone()),
+                (false, 1, Some(generate)) => (generate.clone().into(), paths[0].clone()),
+                (true, 0, _) | (false, 0, _) => {
+                    attr.transparent |= transparent;
+                    continue;
+                }
+                _ => panic!("error_kind must have two arguments or be `transparent`"),
+            };
+            if attr
+                .kinds
+                .iter()
+                .any(|(other, _)| same_path(other, &kind.0))
+            {
+                panic!(
+                    "Variant `{}` sets the `{}` kind twice",
+                    variant.ident,
+                    kind.0.to_token_stream()
+                );
+            }
+            attr.kinds.push(kind);
+        }
+
+        if attr.from && !attr.transparent {
+            panic!("`from` can only be used in transparent variants");
+        }
+        if attr.map.is_some() && !attr.transparent {
+            panic!("`map` can only be used in transparent variants");
+        }
+
+        attr
+    }
+}
+
+struct ErrorVariant {
+    ident: Ident,
+    fields: Fields,
+    attr: VariantAttr,
+    /// Field marked with `#[error_kind(source)]`.
+    source: Option<Member>,
+    /// Transparent variant over a type parameter, which just has the kind accessors.
+    generic: bool,
+}
+
+impl ErrorVariant {
+    fn from_variant(variant: Variant, enum_attr: &EnumAttr, type_params: &[Ident]) -> Self {
+        let source = variant
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| has_flag(field, "source"))
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            })
+            .next();
+
+        let mut error_variant = ErrorVariant {
+            attr: VariantAttr::from_variant(&variant, enum_attr),
+            ident: variant.ident,
+            fields: variant.fields,
+            source,
+            generic: false,
+        };
+        error_variant.generic = error_variant.attr.transparent
+            && uses_type_params(error_variant.transparent_ty(), type_params);
+        error_variant
+    }
+
+    /// Kind variant of the dimension set in the variant.
+    fn kind_or_default<'a>(&'a self, dimension: &'a Dimension) -> Option<&'a Path> {
+        self.attr
+            .kinds
+            .iter()
+            .find(|(kind_ty, _)| same_path(kind_ty, dimension.ty()))
+            .map(|(_, kind)| kind)
+            .or(match self.attr.transparent {
+                true => None,
+                false => dimension.default.as_ref(),
+            })
+    }
+
+    /// Kind variant of the dimension, the variant must not delegate it.
+    fn kind<'a>(&'a self, dimension: &'a Dimension) -> &'a Path {
+        self.kind_or_default(dimension).unwrap_or_else(|| {
+            if self.attr.kinds.is_empty() {
+                panic!("Enum variants must have the attribute `error_kind`");
+            }
+            panic!(
+                "Variant `{}` requires #[error_kind({}, VARIANT)] or a default in the enum",
+                self.ident,
+                dimension.ty().to_token_stream()
+            )
+        })
+    }
+
+    /// Checks if the kind of the dimension is taken from the wrapped error.
+    fn delegates(&self, dimension: &Dimension) -> bool {
+        self.attr.transparent && self.kind_or_default(dimension).is_none()
+    }
+
+    /// Bindings of the fields marked with `#[error_kind(sensitive)]`, `_0` for tuple fields.
+    fn sensitive_bindings(&self) -> Vec<Ident> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| has_flag(field, "sensitive"))
+            .map(|(i, field)| field_binding(i, field))
+            .collect()
+    }
+
+    /// Pattern which ignores the fields of the variant.
+    fn pattern(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        match self.fields {
+            Fields::Unit => quote!(Self::#ident),
+            Fields::Named(_) => quote!(Self::#ident { .. }),
+            Fields::Unnamed(_) => quote!(Self::#ident(..)),
+        }
+    }
+
+    /// Field marked with `#[error_kind(source)]`.
+    fn source_field(&self) -> Option<&Field> {
+        let member = self.source.as_ref()?;
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(i, field)| match (member, &field.ident) {
+                (Member::Named(name), Some(ident)) => name == ident,
+                (Member::Unnamed(index), None) => index.index as usize == *i,
+                _ => false,
+            })
+            .map(|(_, field)| field)
+    }
+
+    /// Wrapped error of a transparent variant, its only field or the field
+    /// marked with `#[error_kind(source)]`.
+    fn transparent_field(&self) -> &Field {
+        match (self.source_field(), self.fields.len()) {
+            (Some(field), _) => field,
+            (None, 1) => self.fields.iter().next().unwrap(),
+            _ => panic!(
+                "Transparent variant `{}` must have one field or a field marked with #[error_kind(source)]",
+                self.ident
+            ),
+        }
+    }
+
+    /// Type of the wrapped error of a transparent variant, without `Box`, `Arc` or `Rc`.
+    fn transparent_ty(&self) -> &Type {
+        let ty = &self.transparent_field().ty;
+        smart_pointee(ty).unwrap_or(ty)
+    }
+
+    /// Reference to the wrapped error bound by [Self::transparent_pattern],
+    /// dereferencing smart pointers.
+    fn inner_ref(&self) -> proc_macro2::TokenStream {
+        if smart_pointee(&self.transparent_field().ty).is_some() {
+            quote!((&**inner))
+        } else {
+            quote!(inner)
+        }
+    }
+
+    /// Member of the wrapped error of a transparent variant.
+    fn transparent_member(&self) -> Member {
+        let field = self.transparent_field();
+        match (&self.source, &field.ident) {
+            (Some(member), _) => member.clone(),
+            (None, Some(ident)) => Member::Named(ident.clone()),
+            (None, None) => Member::Unnamed(0.into()),
+        }
+    }
+
+    /// Pattern which binds the wrapped error of a transparent variant to `inner`.
+    fn transparent_pattern(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let member = self.transparent_member();
+        quote!(Self::#ident { #member: inner, .. })
+    }
+
+    /// Match arm which calls `method` on the wrapped error of a transparent variant.
+    /// A generic wrapped error has no bound for it, so `option` must be set in the
+    /// variant instead.
+    fn transparent_arm(&self, method: &Ident, option: &str) -> proc_macro2::TokenStream {
+        if self.generic {
+            panic!(
+                "`{method}` can't be taken from the generic error wrapped by `{}`, \
+                set #[error_kind(transparent, {option})] in the variant",
+                self.ident
+            );
+        }
+
+        let pattern = self.transparent_pattern();
+        quote! {
+            #pattern => inner.#method(),
+        }
+    }
+}
+
+/// Derive which expands the enum, `ImplKind` just generates the kind accessors.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Derive {
+    ErrorKind,
+    ImplKind,
+}
+
+impl Derive {
+    fn name(self) -> &'static str {
+        match self {
+            Derive::ErrorKind => "ErrorKind",
+            Derive::ImplKind => "ImplKind",
+        }
+    }
+}
+
+/// Enum parsed by `ErrorKind` and `ImplKind`.
+pub struct KindEnum {
+    name: Ident,
+    vis: Visibility,
+    /// Generics of the enum, without the bounds added for the kind traits.
+    generics: Generics,
+    /// Generics with the kind trait bounds of the generic transparent variants.
+    kind_generics: Generics,
+    type_params: Vec<Ident>,
+    enum_attr: EnumAttr,
+    variants: Vec<ErrorVariant>,
+}
+
+impl KindEnum {
+    /// Parses the enum, `ImplKind` doesn't require the kind type in the enum attribute.
+    pub fn parse(input: DeriveInput, derive: Derive) -> Self {
+        let mut enum_attr = EnumAttr::from_input(&input);
+        if derive == Derive::ErrorKind && enum_attr.dimensions.iter().all(|d| d.ty.is_none()) {
+            panic!("#[derive(ErrorKind)] requires #[error_kind(KIND_IDENT)] attribute");
+        }
+
+        let type_params: Vec<_> = input
+            .generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect();
+        let variants: Vec<_> = if let syn::Data::Enum(data) = input.data {
+            data.variants
+                .into_iter()
+                .map(|variant| ErrorVariant::from_variant(variant, &enum_attr, &type_params))
+                .collect()
+        } else {
+            panic!("{} just can be used in enums", derive.name());
+        };
+
+        if variants.is_empty() {
+            panic!("No variants in Enum");
+        }
+
+        if derive == Derive::ImplKind {
+            if let Some(option) = enum_attr.error_kind_option().or_else(|| {
+                variants
+                    .iter()
+                    .find_map(|variant| variant.attr.error_kind_option())
+            }) {
+                panic!("`{}` requires #[derive(ErrorKind)], ImplKind only generates the kind accessors", option);
+            }
+        }
+
+        enum_attr.resolve_dimensions(&variants, derive);
+
+        let mut kind_generics = input.generics.clone();
+        for dimension in &enum_attr.dimensions {
+            let mut bounded_types = Vec::new();
+            for variant in &variants {
+                let ty = match variant.delegates(dimension) {
+                    true => variant.transparent_ty(),
+                    false => continue,
+                };
+                if uses_type_params(ty, &type_params) {
+                    bounded_types.push(ty.clone());
+                }
+            }
+
+            if !bounded_types.is_empty() {
+                let kind_trait = dimension.kind_trait.as_ref().unwrap_or_else(|| {
+                    panic!(
+                        "transparent variants over generic types require a trait with the `{}` method, \
+                        set it with #[error_kind({}, kind_trait = \"path::to::Trait\")]",
+                        dimension.accessor(),
+                        dimension.ty().to_token_stream()
+                    )
+                });
+                let where_clause = kind_generics.make_where_clause();
+                for ty in &bounded_types {
+                    where_clause.predicates.push(parse_quote!(#ty: #kind_trait));
+                }
+            }
+        }
+
+        KindEnum {
+            name: input.ident,
+            vis: input.vis,
+            generics: input.generics,
+            kind_generics,
+            type_params,
+            enum_attr,
+            variants,
+        }
+    }
+
+    /// Generates the accessor of each kind and the `kind_trait` impls.
+    pub fn kind_accessors(&self) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        let (impl_generics, ty_generics, where_clause) = self.kind_generics.split_for_impl();
+        let accessor_fns = self
+            .enum_attr
+            .dimensions
+            .iter()
+            .enumerate()
+            .map(|(i, dimension)| accessor_fn(dimension, i == 0, &self.variants));
+
+        let trait_impls = self.enum_attr.dimensions.iter().filter_map(|dimension| {
+            let kind_trait = dimension.kind_trait.as_ref()?;
+            let kind_ty = dimension.ty();
+            let accessor = dimension.accessor();
+            Some(quote! {
+                impl #impl_generics #kind_trait for #name #ty_generics #where_clause {
+                    fn #accessor(&self) -> #kind_ty {
+                        #name::#accessor(self)
+                    }
+                }
+            })
+        });
+
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                #(#accessor_fns)*
+            }
+
+            #(#trait_impls)*
+        }
+    }
+}
 
 pub fn error_kind_macro(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let kind_ty = get_kind_ty(&input);
+    let kind_enum = KindEnum::parse(input, Derive::ErrorKind);
+    let kind_accessors = kind_enum.kind_accessors();
+
+    let KindEnum {
+        name,
+        vis,
+        generics,
+        kind_generics,
+        type_params,
+        enum_attr,
+        variants,
+    } = &kind_enum;
+    let (impl_generics, ty_generics, where_clause) = kind_generics.split_for_impl();
 
-    let name = input.ident;
-    let variants = if let syn::Data::Enum(data) = input.data {
-        data.variants
+    let exhaustive_checks = enum_attr
+        .dimensions
+        .iter()
+        .filter(|dimension| dimension.exhaustive)
+        .map(|dimension| exhaustive_check(dimension, variants));
+
+    let kind_enum_def = enum_attr
+        .generate
+        .as_ref()
+        .map(|kind_ident| kind_enum_def(name, kind_ident, vis, &enum_attr.dimensions[0], variants));
+    let variant_kinds = match enum_attr.variant_kinds {
+        true => variant_kinds(&enum_attr.dimensions[0], variants),
+        false => quote!(),
+    };
+    let status_code_fn = status_code_fn(enum_attr, variants);
+    let code_fn = code_fn(enum_attr, variants);
+    let grpc_code_fn = grpc_code_fn(enum_attr, variants);
+    let log_level_fn = log_level_fn(enum_attr, variants);
+    let classification_fns = classification_fns(enum_attr, variants);
+    let metric_label_fn = metric_label_fn(enum_attr, variants);
+    let constructor_fns = match enum_attr.constructors {
+        true => {
+            let mut methods: Vec<_> = enum_attr
+                .dimensions
+                .iter()
+                .map(|dimension| dimension.accessor().to_string())
+                .collect();
+            let generated = [
+                (&variant_kinds, &["kind_of_variant"][..]),
+                (&status_code_fn, &["status_code"]),
+                (&code_fn, &["code"]),
+                (&grpc_code_fn, &["grpc_code"]),
+                (&log_level_fn, &["log_level"]),
+                (
+                    &classification_fns,
+                    &["is_retryable", "is_client_error", "is_server_error"],
+                ),
+                (&metric_label_fn, &["metric_label"]),
+            ];
+            for (tokens, names) in generated {
+                if !tokens.is_empty() {
+                    methods.extend(names.iter().map(|name| name.to_string()));
+                }
+            }
+            if enum_attr.problem_details.is_some() {
+                methods.push("to_problem_details".to_string());
+            }
+
+            variants
+                .iter()
+                .map(|variant| constructor_fn(variant, &methods))
+                .collect()
+        }
+        false => Vec::new(),
+    };
+    let display_impl = display_impl(name, generics, variants, type_params);
+    let debug_impl = debug_impl(name, generics, variants, type_params);
+    let from_impls = from_impls(name, generics, variants);
+    let (problem_details_fn, problem_details_def) = match &enum_attr.problem_details {
+        Some(base_uri) => problem_details(
+            name,
+            vis,
+            generics,
+            &enum_attr.dimensions[0],
+            base_uri,
+            variants,
+            type_params,
+        ),
+        None => (quote!(), quote!()),
+    };
+    let error_impl = if enum_attr.impl_error {
+        error_impl(name, generics, variants, type_params)
     } else {
-        panic!("ImplKind just can be used in enums");
+        quote!()
     };
 
-    let mut kind_variants = Vec::new();
+    let expanded = quote! {
+        #kind_enum_def
 
-    for variant in variants.clone() {
-        let ident = variant.ident;
-        if let Some(attr) = variant
-            .attrs
-            .into_iter()
-            .find(|attr| attr.path.is_ident("error_kind"))
-        {
-            if let Ok(syn::Meta::List(meta)) = attr.parse_meta() {
-                if meta.nested.len() == 2 {
-                    if let (
-                        syn::NestedMeta::Meta(syn::Meta::Path(enum_ty)),
-                        syn::NestedMeta::Meta(syn::Meta::Path(variant)),
-                    ) = (&meta.nested[0], &meta.nested[1])
+        #kind_accessors
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #variant_kinds
+
+            #status_code_fn
+
+            #code_fn
+
+            #grpc_code_fn
+
+            #log_level_fn
+
+            #classification_fns
+
+            #metric_label_fn
+
+            #(#constructor_fns)*
+
+            #problem_details_fn
+        }
+
+        #problem_details_def
+
+        #(#exhaustive_checks)*
+
+        #display_impl
+
+        #debug_impl
+
+        #error_impl
+
+        #(#from_impls)*
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Generates the accessor of a dimension, `kind()` for the first one. It's a
+/// `const fn` when no variant takes the kind from the wrapped error.
+fn accessor_fn(
+    dimension: &Dimension,
+    is_kind: bool,
+    variants: &[ErrorVariant],
+) -> proc_macro2::TokenStream {
+    let kind_ty = dimension.ty();
+    let accessor = dimension.accessor();
+    let match_arms = variants.iter().map(|variant| {
+        if !variant.delegates(dimension) {
+            let kind_variant = variant.kind(dimension);
+            let pattern = variant.pattern();
+            return quote! {
+                #pattern => #kind_ty::#kind_variant,
+            };
+        }
+
+        let pattern = variant.transparent_pattern();
+        let kind = match (&variant.attr.map, is_kind) {
+            (Some(KindMap::Fn(map)), true) => quote!(#map(inner.#accessor())),
+            (Some(KindMap::From), true) => {
+                quote!(::std::convert::Into::<#kind_ty>::into(inner.#accessor()))
+            }
+            _ => {
+                // Reports a wrapped error of another kind type at the variant, with
+                // the way to convert it.
+                let message = format!(
+                    "the `{accessor}` of the error wrapped by `{}` isn't a `{}`",
+                    variant.ident,
+                    kind_ty.to_token_stream()
+                );
+                let label = match is_kind {
+                    true => "convert it with `map = path::to::fn` or `map(From)`",
+                    false => "transparent variants require the same type",
+                };
+                quote_spanned! {variant.ident.span()=>
                     {
-                        kind_variants.push((ident, enum_ty.clone(), Some(variant.clone())));
-                    } else {
-                        panic!("Invalid value for error_kind");
-                    }
-                } else if meta.nested.len() == 1 {
-                    for sub_meta in meta.nested {
-                        if let NestedMeta::Meta(Meta::Path(path)) = sub_meta {
-                            if path.is_ident("transparent") {
-                                kind_variants.push((ident.clone(), kind_ty.clone(), None));
+                        #[diagnostic::on_unimplemented(message = #message, label = #label)]
+                        trait SameKind<K> {
+                            fn same_kind(self) -> K;
+                        }
+
+                        impl<K> SameKind<K> for K {
+                            fn same_kind(self) -> K {
+                                self
                             }
-                        } else {
-                            panic!("Invalid value for #[error_kind]");
                         }
+
+                        SameKind::<#kind_ty>::same_kind(inner.#accessor())
                     }
-                } else {
-                    panic!("error_kind must have one two arguments");
                 }
-            } else {
-                panic!("Error parsing meta");
             }
-        } else {
-            panic!("Enum variants must have the attribute `error_kind`");
+        };
+        quote! {
+            #pattern => #kind,
+        }
+    });
+
+    let constness = variants
+        .iter()
+        .all(|variant| !variant.delegates(dimension))
+        .then(|| quote!(const));
+
+    let doc = format!("Returns the `{}` of the error.", kind_ty.to_token_stream());
+    quote! {
+        #[doc = #doc]
+        pub #constness fn #accessor(&self) -> #kind_ty {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    }
+}
+
+/// Generates the `VARIANT_KINDS` table and `kind_of_variant` with `variant_kinds`,
+/// with the kind of each variant by name, except the ones which take it from the
+/// wrapped error.
+fn variant_kinds(kind: &Dimension, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    let kind_ty = kind.ty();
+    let (names, kinds): (Vec<_>, Vec<_>) = variants
+        .iter()
+        .filter(|variant| !variant.delegates(kind))
+        .map(|variant| (variant.ident.to_string(), variant.kind(kind)))
+        .unzip();
+
+    quote! {
+        /// Name and kind of each variant which doesn't take the kind from a wrapped error.
+        pub const VARIANT_KINDS: &'static [(&'static str, #kind_ty)] = &[
+            #((#names, #kind_ty::#kinds)),*
+        ];
+
+        /// Returns the kind of the variant with the given name.
+        pub fn kind_of_variant(name: &str) -> Option<#kind_ty> {
+            match name {
+                #(#names => Some(#kind_ty::#kinds),)*
+                _ => None,
+            }
         }
     }
+}
 
-    let kind_enum = kind_variants
-        .first()
-        .expect("No variants in Enum")
-        .1
-        .clone();
-    let match_arms = kind_variants.into_iter().map(|(ident, enum_ty, variant)| {
-        let fields = &variants.iter().find(|v| v.ident == ident).unwrap().fields;
-        match fields {
-            syn::Fields::Unit => {
-                quote! {
-                    Self::#ident => #enum_ty::#variant,
+/// Generates a match over the kind type with the kinds used by the variants and
+/// the ones in `allow_unused`, which doesn't compile when a kind isn't used.
+fn exhaustive_check(dimension: &Dimension, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    let kind_ty = dimension.ty();
+    let mut kinds: Vec<&Path> = Vec::new();
+    let used = variants
+        .iter()
+        .filter(|variant| !variant.delegates(dimension))
+        .map(|variant| variant.kind(dimension));
+    for kind in used.chain(&dimension.allow_unused) {
+        if !kinds.iter().any(|other| same_path(other, kind)) {
+            kinds.push(kind);
+        }
+    }
+
+    // Spanned to the kind type, so the unused kinds are reported in the enum attribute.
+    let check = quote_spanned! {kind_ty.span()=>
+        match kind {
+            #(#kind_ty::#kinds)|* => {}
+        }
+    };
+
+    quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn exhaustive(kind: #kind_ty) {
+                #check
+            }
+        };
+    }
+}
+
+/// Generates the kind enum set with `generate`, with a variant for each kind
+/// used in the error variants.
+fn kind_enum_def(
+    name: &Ident,
+    kind_ident: &Ident,
+    vis: &Visibility,
+    dimension: &Dimension,
+    variants: &[ErrorVariant],
+) -> proc_macro2::TokenStream {
+    // Each kind with the error variants which use it.
+    let mut kinds: Vec<(&Ident, Vec<String>)> = Vec::new();
+    for variant in variants {
+        let Some(kind) = variant.kind_or_default(dimension) else {
+            continue;
+        };
+        let kind = kind.get_ident().expect("Kind variants must be identifiers");
+        let variant_name = format!("`{}`", variant.ident);
+        match kinds.iter_mut().find(|(other, _)| *other == kind) {
+            Some((_, used_by)) => used_by.push(variant_name),
+            None => kinds.push((kind, vec![variant_name])),
+        }
+    }
+
+    if kinds.is_empty() {
+        panic!(
+            "`generate = {kind_ident}` requires a variant with a kind, \
+            all the variants of `{name}` take it from the wrapped error"
+        );
+    }
+
+    let kind_docs = kinds
+        .iter()
+        .map(|(_, used_by)| format!("Kind of {}.", used_by.join(", ")));
+    let (kinds, _): (Vec<_>, Vec<_>) = kinds.iter().cloned().unzip();
+    let names: Vec<_> = kinds.iter().map(|kind| kind.to_string()).collect();
+    let unknown = format!("unknown {kind_ident} `{{}}`");
+    let doc = format!("Kinds of the `{name}` errors.");
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #vis enum #kind_ident {
+            #(
+                #[doc = #kind_docs]
+                #kinds,
+            )*
+        }
+
+        impl #kind_ident {
+            /// All the kinds, in order of declaration.
+            pub const ALL: &'static [#kind_ident] = &[#(#kind_ident::#kinds),*];
+
+            /// Returns the name of the kind, which `FromStr` parses back.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    #(Self::#kinds => #names,)*
+                }
+            }
+        }
+
+        impl ::std::str::FromStr for #kind_ident {
+            type Err = ::std::string::String;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                match s {
+                    #(#names => ::std::result::Result::Ok(Self::#kinds),)*
+                    _ => ::std::result::Result::Err(::std::format!(#unknown, s)),
                 }
             }
-            syn::Fields::Named(_) => {
-                quote! {
-                    Self::#ident{..} => #enum_ty::#variant,
+        }
+    }
+}
+
+/// Generates `status_code` when the enum or any variant sets a `status`, or
+/// `problem_details` requires it.
+fn status_code_fn(enum_attr: &EnumAttr, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    if enum_attr.status.is_none()
+        && enum_attr.problem_details.is_none()
+        && variants.iter().all(|v| v.attr.status.is_none())
+    {
+        return quote!();
+    }
+
+    let method = Ident::new("status_code", proc_macro2::Span::call_site());
+    let match_arms = variants.iter().map(|variant| {
+        let status = match (variant.attr.status, variant.attr.transparent) {
+            (Some(status), _) => status,
+            (None, true) => return variant.transparent_arm(&method, "status = CODE"),
+            (None, false) => enum_attr.status.unwrap_or_else(|| {
+                panic!(
+                    "Variant `{}` requires #[error_kind(..., status = CODE)] or a default status in the enum",
+                    variant.ident
+                )
+            }),
+        };
+        let pattern = variant.pattern();
+        quote! {
+            #pattern => #status,
+        }
+    });
+
+    quote! {
+        /// Returns the HTTP status code of the error.
+        pub fn status_code(&self) -> u16 {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    }
+}
+
+/// Generates `code` and the `CODES` const when the enum sets `code` or
+/// `code_prefix`, or any variant sets a `code`. With `code` or `code_prefix` in the
+/// enum, the variants without `code` use the prefix and their name in screaming
+/// snake case, otherwise they require it.
+fn code_fn(enum_attr: &EnumAttr, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    if enum_attr.code_prefix.is_none() && variants.iter().all(|v| v.attr.code.is_none()) {
+        return quote!();
+    }
+
+    let method = Ident::new("code", proc_macro2::Span::call_site());
+    let mut codes: Vec<(String, &Ident)> = Vec::new();
+    let match_arms: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.ident;
+            let code = match (&variant.attr.code, variant.attr.transparent) {
+                (Some(code), _) => code.clone(),
+                (None, true) => return variant.transparent_arm(&method, r#"code = "CODE""#),
+                (None, false) => {
+                    let prefix = enum_attr.code_prefix.as_ref().unwrap_or_else(|| {
+                        panic!(
+                            "Variant `{ident}` requires #[error_kind(..., code = \"...\")] \
+                            or `code`/`code_prefix` in the enum"
+                        )
+                    });
+                    format!(
+                        "{prefix}{}",
+                        to_snake_case(&ident.to_string()).to_uppercase()
+                    )
                 }
+            };
+            if let Some((_, other)) = codes.iter().find(|(other, _)| *other == code) {
+                panic!("`{ident}` and `{other}` both use the code `{code}`");
             }
-            syn::Fields::Unnamed(_) => match variant {
-                Some(v) => quote! {
-                    Self::#ident(..) => #enum_ty::#v,
+            codes.push((code.clone(), ident));
+
+            let pattern = variant.pattern();
+            quote! {
+                #pattern => #code,
+            }
+        })
+        .collect();
+    let codes = codes.iter().map(|(code, _)| code);
+
+    quote! {
+        /// Codes of the variants which don't take it from a wrapped error.
+        pub const CODES: &'static [&'static str] = &[#(#codes),*];
+
+        /// Returns the stable code of the error.
+        pub fn code(&self) -> &'static str {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    }
+}
+
+/// Generates `grpc_code` when the enum sets `grpc`, a default or a `grpc(...)` table, or any
+/// variant sets `grpc`. The variants take the code from their `grpc`, the table
+/// entry of their kind or the default of the enum.
+fn grpc_code_fn(enum_attr: &EnumAttr, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    if !enum_attr.grpc_code && variants.iter().all(|v| v.attr.grpc.is_none()) {
+        return quote!();
+    }
+
+    let method = Ident::new("grpc_code", proc_macro2::Span::call_site());
+    let kind = &enum_attr.dimensions[0];
+    let match_arms = variants.iter().map(|variant| {
+        let grpc = match (&variant.attr.grpc, variant.attr.transparent) {
+            (Some(grpc), _) => grpc,
+            (None, true) => return variant.transparent_arm(&method, "grpc = CODE"),
+            (None, false) => {
+                let kind_variant = variant.kind(kind);
+                enum_attr
+                    .grpc_table
+                    .iter()
+                    .find(|(table_kind, _)| same_path(table_kind, kind_variant))
+                    .map(|(_, grpc)| grpc)
+                    .or(enum_attr.grpc.as_ref())
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Variant `{}` requires #[error_kind(..., grpc = CODE)], an entry in the grpc table or a default in the enum",
+                            variant.ident
+                        )
+                    })
+            }
+        };
+        let code = grpc_code(grpc);
+        let pattern = variant.pattern();
+        quote! {
+            #pattern => #code,
+        }
+    });
+
+    quote! {
+        /// Returns the canonical gRPC status code of the error.
+        pub fn grpc_code(&self) -> i32 {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    }
+}
+
+/// Numeric value of a canonical gRPC status code.
+fn grpc_code(name: &Path) -> i32 {
+    let name = name.to_token_stream().to_string();
+    match name.as_str() {
+        "Ok" => 0,
+        "Cancelled" => 1,
+        "Unknown" => 2,
+        "InvalidArgument" => 3,
+        "DeadlineExceeded" => 4,
+        "NotFound" => 5,
+        "AlreadyExists" => 6,
+        "PermissionDenied" => 7,
+        "ResourceExhausted" => 8,
+        "FailedPrecondition" => 9,
+        "Aborted" => 10,
+        "OutOfRange" => 11,
+        "Unimplemented" => 12,
+        "Internal" => 13,
+        "Unavailable" => 14,
+        "DataLoss" => 15,
+        "Unauthenticated" => 16,
+        _ => panic!("Unknown gRPC code `{name}`"),
+    }
+}
+
+/// Generates `is_retryable`, `is_client_error` and `is_server_error` with the
+/// enum `classification` flag or when any variant sets `retryable` or `client_error`.
+/// Without the flags, the retryable statuses are 408, 429, 502, 503 and 504, and
+/// the variants without status are server errors.
+fn classification_fns(enum_attr: &EnumAttr, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    let has_flags = variants
+        .iter()
+        .any(|v| v.attr.retryable || v.attr.client_error);
+    if !enum_attr.classification && !has_flags {
+        return quote!();
+    }
+
+    let is_retryable = predicate_fn("is_retryable", variants, |variant| {
+        match (variant.attr.retryable, variant_status(enum_attr, variant)) {
+            (true, _) => Some(true),
+            (false, Some(status)) => Some(matches!(status, 408 | 429 | 502 | 503 | 504)),
+            (false, None) => (!variant.attr.transparent).then_some(false),
+        }
+    });
+    let is_client_error = predicate_fn("is_client_error", variants, |variant| {
+        match (
+            variant.attr.client_error,
+            variant_status(enum_attr, variant),
+        ) {
+            (true, _) => Some(true),
+            (false, Some(status)) => Some((400..500).contains(&status)),
+            (false, None) => (!variant.attr.transparent).then_some(false),
+        }
+    });
+    let is_server_error = predicate_fn("is_server_error", variants, |variant| {
+        match (
+            variant.attr.client_error,
+            variant_status(enum_attr, variant),
+        ) {
+            (true, _) => Some(false),
+            (false, Some(status)) => Some((500..600).contains(&status)),
+            (false, None) => (!variant.attr.transparent).then_some(true),
+        }
+    });
+
+    quote! {
+        #is_retryable
+
+        #is_client_error
+
+        #is_server_error
+    }
+}
+
+/// Status of the variant, the default of the enum doesn't apply to transparent variants.
+fn variant_status(enum_attr: &EnumAttr, variant: &ErrorVariant) -> Option<u16> {
+    match variant.attr.transparent {
+        true => variant.attr.status,
+        false => variant.attr.status.or(enum_attr.status),
+    }
+}
+
+/// Generates a predicate with the value of each variant, `None` delegates it to
+/// the wrapped error.
+fn predicate_fn(
+    method: &str,
+    variants: &[ErrorVariant],
+    value: impl Fn(&ErrorVariant) -> Option<bool>,
+) -> proc_macro2::TokenStream {
+    let method = Ident::new(method, proc_macro2::Span::call_site());
+    let match_arms = variants.iter().map(|variant| {
+        let Some(value) = value(variant) else {
+            return variant.transparent_arm(&method, "status = CODE");
+        };
+        let pattern = variant.pattern();
+        quote! {
+            #pattern => #value,
+        }
+    });
+
+    let doc = match method.to_string().as_str() {
+        "is_retryable" => "Returns whether the operation which failed can be retried.",
+        "is_client_error" => "Returns whether the error was caused by the client.",
+        _ => "Returns whether the error was caused by the server.",
+    };
+    quote! {
+        #[doc = #doc]
+        pub fn #method(&self) -> bool {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    }
+}
+
+/// Generates `log_level` when the enum or any variant sets a `level`.
+fn log_level_fn(enum_attr: &EnumAttr, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    if enum_attr.level.is_none() && variants.iter().all(|v| v.attr.level.is_none()) {
+        return quote!();
+    }
+
+    let method = Ident::new("log_level", proc_macro2::Span::call_site());
+    let match_arms = variants.iter().map(|variant| {
+        let level = match (&variant.attr.level, variant.attr.transparent) {
+            (Some(level), _) => level,
+            (None, true) => return variant.transparent_arm(&method, "level = LEVEL"),
+            (None, false) => enum_attr.level.as_ref().unwrap_or_else(|| {
+                panic!(
+                    "Variant `{}` requires #[error_kind(..., level = LEVEL)] or a default level in the enum",
+                    variant.ident
+                )
+            }),
+        };
+        let pattern = variant.pattern();
+        quote! {
+            #pattern => #level,
+        }
+    });
+
+    quote! {
+        /// Returns the level the error should be logged with.
+        pub fn log_level(&self) -> &'static str {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    }
+}
+
+/// Checks the name of a log level.
+fn log_level(level: &Path) -> String {
+    let level = level.to_token_stream().to_string();
+    match level.as_str() {
+        "trace" | "debug" | "info" | "warn" | "error" => level,
+        _ => panic!(
+            "Unknown log level `{level}`, expected `trace`, `debug`, `info`, `warn` or `error`"
+        ),
+    }
+}
+
+/// Generates `metric_label` when the enum sets `metric` or any variant sets a
+/// `metric`. The variants without it use the default of the enum, or their kind
+/// in snake case.
+fn metric_label_fn(enum_attr: &EnumAttr, variants: &[ErrorVariant]) -> proc_macro2::TokenStream {
+    if !enum_attr.metric_label && variants.iter().all(|v| v.attr.metric.is_none()) {
+        return quote!();
+    }
+
+    let method = Ident::new("metric_label", proc_macro2::Span::call_site());
+    let kind = &enum_attr.dimensions[0];
+    let match_arms = variants.iter().map(|variant| {
+        let metric = match (&variant.attr.metric, variant.attr.transparent) {
+            (Some(metric), _) => metric.clone(),
+            (None, true) => return variant.transparent_arm(&method, r#"metric = "LABEL""#),
+            (None, false) => enum_attr.metric.clone().unwrap_or_else(|| {
+                let kind = variant.kind(kind).segments.last().unwrap();
+                to_snake_case(&kind.ident.to_string())
+            }),
+        };
+        let pattern = variant.pattern();
+        quote! {
+            #pattern => #metric,
+        }
+    });
+
+    quote! {
+        /// Returns the label of the error in metrics.
+        pub fn metric_label(&self) -> &'static str {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    }
+}
+
+/// Generates the snake case constructor of a variant, whose arguments take
+/// `impl Into<T>`. The field marked with `#[error_kind(location)]` is filled
+/// with the location of the caller.
+fn constructor_fn(variant: &ErrorVariant, methods: &[String]) -> proc_macro2::TokenStream {
+    let ident = &variant.ident;
+    let constructor = constructor_ident(ident, methods);
+    let mut args = Vec::new();
+    let mut values = Vec::new();
+    for (i, field) in variant.fields.iter().enumerate() {
+        if has_flag(field, "location") {
+            values.push(quote!(::std::panic::Location::caller()));
+            continue;
+        }
+
+        let arg = match &field.ident {
+            Some(field_ident) => field_ident.clone(),
+            None => format_ident!("arg{i}"),
+        };
+        let ty = &field.ty;
+        args.push(quote!(#arg: impl ::std::convert::Into<#ty>));
+        values.push(quote!(::std::convert::Into::into(#arg)));
+    }
+
+    let value = match &variant.fields {
+        Fields::Unit => quote!(Self::#ident),
+        Fields::Named(fields) => {
+            let members = fields.named.iter().map(|field| &field.ident);
+            quote!(Self::#ident { #(#members: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(Self::#ident(#(#values),*)),
+    };
+
+    let doc = format!("Creates a `{ident}` error.");
+    quote! {
+        #[doc = #doc]
+        #[track_caller]
+        pub fn #constructor(#(#args),*) -> Self {
+            #value
+        }
+    }
+}
+
+/// Snake case name of the constructor of a variant, raw when it is a keyword.
+fn constructor_ident(variant: &Ident, methods: &[String]) -> Ident {
+    let name = to_snake_case(&variant.unraw().to_string());
+    if methods.contains(&name) {
+        panic!(
+            "the constructor `{}` of variant `{}` collides with a method generated by ErrorKind, \
+            rename the variant or remove `constructors`",
+            name, variant
+        );
+    }
+
+    match syn::parse_str::<Ident>(&name) {
+        Ok(_) if name != "gen" => Ident::new(&name, variant.span()),
+        _ if ["self", "super", "crate"].contains(&name.as_str()) => {
+            panic!(
+                "the constructor of variant `{}` would be the keyword `{}`",
+                variant, name
+            )
+        }
+        _ => Ident::new_raw(&name, variant.span()),
+    }
+}
+
+/// Binding of a field in the generated `match` arms, prefixed so it can't shadow
+/// the formatter or other locals.
+fn field_binding(index: usize, field: &Field) -> Ident {
+    match &field.ident {
+        Some(ident) => format_ident!("__field_{}", ident.unraw()),
+        None => format_ident!("__field_{index}"),
+    }
+}
+
+/// Generates `Debug` when any field is marked with `#[error_kind(sensitive)]`,
+/// printing `[REDACTED]` instead of its value.
+fn debug_impl(
+    name: &Ident,
+    generics: &Generics,
+    variants: &[ErrorVariant],
+    type_params: &[Ident],
+) -> proc_macro2::TokenStream {
+    if variants
+        .iter()
+        .all(|variant| variant.sensitive_bindings().is_empty())
+    {
+        return quote!();
+    }
+
+    let mut generics = generics.clone();
+    let match_arms: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.ident;
+            let ident_str = ident.to_string();
+            let mut bindings = Vec::new();
+            let mut values = Vec::new();
+            for (i, field) in variant.fields.iter().enumerate() {
+                let binding = field_binding(i, field);
+                let value = if has_flag(field, "sensitive") {
+                    bindings.push(match &field.ident {
+                        Some(field_ident) => quote!(#field_ident: _),
+                        None => quote!(_),
+                    });
+                    quote!(&format_args!("[REDACTED]"))
+                } else {
+                    let ty = &field.ty;
+                    if uses_type_params(ty, type_params) {
+                        generics
+                            .make_where_clause()
+                            .predicates
+                            .push(parse_quote!(#ty: ::std::fmt::Debug));
+                    }
+                    bindings.push(match &field.ident {
+                        Some(field_ident) => quote!(#field_ident: #binding),
+                        None => quote!(#binding),
+                    });
+                    quote!(#binding)
+                };
+                values.push(match &field.ident {
+                    Some(field_ident) => {
+                        let field_str = field_ident.to_string();
+                        quote!(.field(#field_str, #value))
+                    }
+                    None => quote!(.field(#value)),
+                });
+            }
+
+            match &variant.fields {
+                Fields::Unit => quote! {
+                    Self::#ident => formatter.write_str(#ident_str),
                 },
-                None => quote! {
-                    Self::#ident(inner) => inner.kind(),
+                Fields::Named(_) => {
+                    quote! {
+                        Self::#ident { #(#bindings),* } => {
+                            formatter.debug_struct(#ident_str) #(#values)* .finish()
+                        }
+                    }
+                }
+                Fields::Unnamed(_) => quote! {
+                    Self::#ident(#(#bindings),*) => {
+                        formatter.debug_tuple(#ident_str) #(#values)* .finish()
+                    }
                 },
-            },
+            }
+        })
+        .collect();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, formatter: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    #(#match_arms)*
+                }
+            }
         }
-    });
+    }
+}
 
-    let expanded = quote! {
-        impl #name {
-            pub fn kind(&self) -> #kind_enum {
+/// Generates `Display` from the `message` of the variants, when any variant sets it.
+fn display_impl(
+    name: &Ident,
+    generics: &Generics,
+    variants: &[ErrorVariant],
+    type_params: &[Ident],
+) -> proc_macro2::TokenStream {
+    if variants.iter().all(|v| v.attr.message.is_none()) {
+        return quote!();
+    }
+
+    let mut generics = generics.clone();
+    let match_arms: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.ident;
+            let Some(message) = &variant.attr.message else {
+                if !variant.attr.transparent {
+                    panic!(
+                        "Variant `{ident}` requires #[error_kind(..., message = \"...\")] \
+                        because other variants have a message"
+                    );
+                }
+
+                let ty = variant.transparent_ty();
+                if uses_type_params(ty, type_params) {
+                    generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!(#ty: ::std::fmt::Display));
+                }
+                let pattern = variant.transparent_pattern();
+                let inner = variant.inner_ref();
+                return quote! {
+                    #pattern => ::std::fmt::Display::fmt(#inner, formatter),
+                };
+            };
+
+            let (format, used) = message_format(message, variant);
+            let format = LitStr::new(&format, Span::call_site());
+            let mut args = Vec::new();
+            let mut bindings = Vec::new();
+            let mut redacted = Vec::new();
+            let mut members = Vec::new();
+            for (i, field) in variant.fields.iter().enumerate() {
+                let format_traits: Vec<_> = used
+                    .iter()
+                    .filter(|(field, _)| *field == i)
+                    .map(|(_, format_trait)| format_trait)
+                    .collect();
+                if format_traits.is_empty() {
+                    continue;
+                }
+
+                let binding = field_binding(i, field);
+                args.push(match &field.ident {
+                    Some(field_ident) => field_ident.clone(),
+                    None => format_ident!("_{i}"),
+                });
+                bindings.push(binding.clone());
+                if has_flag(field, "sensitive") {
+                    redacted.push(binding);
+                    continue;
+                }
+
+                let ty = &field.ty;
+                if uses_type_params(ty, type_params) {
+                    let predicates = &mut generics.make_where_clause().predicates;
+                    for format_trait in format_traits {
+                        predicates.push(parse_quote!(#ty: ::std::fmt::#format_trait));
+                    }
+                }
+                members.push((i, field));
+            }
+            let pattern = match &variant.fields {
+                Fields::Unit => quote!(Self::#ident),
+                Fields::Named(_) => {
+                    let members = members.iter().map(|(i, field)| {
+                        let field_ident = &field.ident;
+                        let binding = field_binding(*i, field);
+                        quote!(#field_ident: #binding)
+                    });
+                    quote!(Self::#ident { #(#members,)* .. })
+                }
+                Fields::Unnamed(_) => {
+                    let bindings = variant.fields.iter().enumerate().map(|(i, field)| {
+                        if members.iter().any(|(member, _)| *member == i) {
+                            let binding = field_binding(i, field);
+                            quote!(#binding)
+                        } else {
+                            quote!(_)
+                        }
+                    });
+                    quote!(Self::#ident(#(#bindings),*))
+                }
+            };
+
+            quote! {
+                #pattern => {
+                    #(let #redacted = "[REDACTED]";)*
+                    write!(formatter, #format, #(#args = #bindings),*)
+                }
+            }
+        })
+        .collect();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, formatter: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
                 match self {
                     #(#match_arms)*
                 }
             }
         }
+    }
+}
+
+/// Generates `to_problem_details`, which returns an RFC 7807 problem details
+/// struct serialized with serde. The type and title come from the kind, the
+/// detail from `Display` and the fields marked with `#[error_kind(extension)]`
+/// are added as extension members.
+fn problem_details(
+    name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    kind: &Dimension,
+    base_uri: &str,
+    variants: &[ErrorVariant],
+    type_params: &[Ident],
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let problem_ident = format_ident!("{name}ProblemDetails");
+    let kind_ty = kind.ty();
+    let accessor = kind.accessor();
+
+    // Lifetime of the borrowed error, which mustn't clash with the ones of the enum.
+    let mut lifetime = "problem".to_string();
+    while generics
+        .lifetimes()
+        .any(|param| param.lifetime.ident == lifetime)
+    {
+        lifetime.push('_');
+    }
+    let lifetime = syn::Lifetime::new(&format!("'{lifetime}"), Span::call_site());
+
+    let mut problem_generics = generics.clone();
+    problem_generics.params.insert(0, parse_quote!(#lifetime));
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let (_, problem_ty_generics, _) = problem_generics.split_for_impl();
+
+    let mut serialize_generics = problem_generics.clone();
+    let extension_arms: Vec<_> = variants
+        .iter()
+        .filter_map(|variant| {
+            let fields: Vec<_> = variant
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| has_flag(field, "extension"))
+                .collect();
+            if fields.is_empty() {
+                return None;
+            }
+
+            let mut bindings = Vec::new();
+            let mut entries = Vec::new();
+            for (i, field) in fields {
+                let ident = field.ident.as_ref().unwrap_or_else(|| {
+                    panic!("Extension fields of `{}` must be named", variant.ident)
+                });
+                let entry = ident.to_string();
+                let binding = field_binding(i, field);
+                if has_flag(field, "sensitive") {
+                    entries.push(quote!(map.serialize_entry(#entry, "[REDACTED]")?;));
+                    continue;
+                }
+
+                let ty = &field.ty;
+                if uses_type_params(ty, type_params) {
+                    serialize_generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!(#ty: ::serde::Serialize));
+                }
+                entries.push(quote!(map.serialize_entry(#entry, #binding)?;));
+                bindings.push(quote!(#ident: #binding));
+            }
+            let ident = &variant.ident;
+            Some(quote! {
+                #name::#ident { #(#bindings,)* .. } => {
+                    #(#entries)*
+                }
+            })
+        })
+        .collect();
+    let (impl_generics, _, serialize_where_clause) = serialize_generics.split_for_impl();
+
+    let problem_fn = quote! {
+        /// Returns the RFC 7807 problem details of the error.
+        pub fn to_problem_details<#lifetime>(&#lifetime self) -> #problem_ident #problem_ty_generics
+        where
+            Self: ::std::fmt::Display,
+            #kind_ty: ::std::fmt::Debug,
+        {
+            let kind = format!("{:?}", self.#accessor());
+            let mut slug = String::new();
+            let mut title = String::new();
+            for (i, c) in kind.chars().enumerate() {
+                if i > 0 && c.is_uppercase() {
+                    slug.push('-');
+                    title.push(' ');
+                }
+                slug.extend(c.to_lowercase());
+                if i == 0 {
+                    title.push(c);
+                } else {
+                    title.extend(c.to_lowercase());
+                }
+            }
+
+            #problem_ident {
+                problem_type: format!("{}{}", #base_uri, slug),
+                title,
+                status: self.status_code(),
+                detail: ::std::string::ToString::to_string(self),
+                error: self,
+            }
+        }
     };
 
-    TokenStream::from(expanded)
+    let doc = format!(
+        "RFC 7807 problem details of a `{name}`, serialized with the extension members of its variant."
+    );
+    let problem_def = quote! {
+        #[doc = #doc]
+        #vis struct #problem_ident #problem_generics #where_clause {
+            /// URI reference which identifies the problem type, serialized as `type`.
+            pub problem_type: String,
+            /// Short summary of the problem type.
+            pub title: String,
+            /// HTTP status code of the error.
+            pub status: u16,
+            /// Explanation of this occurrence of the problem.
+            pub detail: String,
+            error: &#lifetime #name #ty_generics,
+        }
+
+        impl #impl_generics ::serde::Serialize for #problem_ident #problem_ty_generics
+            #serialize_where_clause
+        {
+            fn serialize<__S: ::serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::std::result::Result<__S::Ok, __S::Error> {
+                use ::serde::ser::SerializeMap;
+
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_entry("type", &self.problem_type)?;
+                map.serialize_entry("title", &self.title)?;
+                map.serialize_entry("status", &self.status)?;
+                map.serialize_entry("detail", &self.detail)?;
+                #[allow(unreachable_patterns)]
+                match self.error {
+                    #(#extension_arms)*
+                    _ => {}
+                }
+                map.end()
+            }
+        }
+    };
+
+    (problem_fn, problem_def)
 }
 
-fn get_kind_ty(input: &DeriveInput) -> Path {
-    let metas = find_attribute(input, "error_kind")
-        .expect("#[derive(ErrorKind)] requires error_kind attribute");
-    if let Some(&NestedMeta::Meta(Meta::Path(ref path))) = metas.iter().next() {
-        path.to_owned()
-    } else {
-        panic!("#[error_kind(KIND_IDENT)] attribute requires and identifier");
+/// Generates `std::error::Error`, whose `source` is the wrapped error of the
+/// transparent variants or the field marked with `#[error_kind(source)]`.
+fn error_impl(
+    name: &Ident,
+    generics: &Generics,
+    variants: &[ErrorVariant],
+    type_params: &[Ident],
+) -> proc_macro2::TokenStream {
+    let mut generics = generics.clone();
+    let match_arms: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            if !variant.attr.transparent && variant.source.is_none() {
+                let pattern = variant.pattern();
+                return quote! {
+                    #pattern => None,
+                };
+            }
+
+            let source_ty = variant.transparent_ty();
+            if uses_type_params(source_ty, type_params) {
+                generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote!(#source_ty: ::std::error::Error + 'static));
+            }
+
+            let pattern = variant.transparent_pattern();
+            let inner = variant.inner_ref();
+            quote! {
+                #pattern => Some(#inner),
+            }
+        })
+        .collect();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::std::error::Error for #name #ty_generics #where_clause {
+            fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+                match self {
+                    #(#match_arms)*
+                }
+            }
+        }
+    }
+}
+
+/// Generates `From` for the transparent variants marked with `from`.
+fn from_impls(
+    name: &Ident,
+    generics: &Generics,
+    variants: &[ErrorVariant],
+) -> Vec<proc_macro2::TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut source_types: Vec<(String, &Ident)> = Vec::new();
+    variants
+        .iter()
+        .filter(|variant| variant.attr.from)
+        .map(|variant| {
+            let ident = &variant.ident;
+            if variant.fields.len() != 1 {
+                panic!("`from` requires `{ident}` to have just the wrapped error");
+            }
+
+            // `Box<T>`, `Arc<T>` and `Rc<T>` implement `From<T>`, except for trait objects.
+            let field_ty = &variant.transparent_field().ty;
+            let ty = match smart_pointee(field_ty) {
+                Some(Type::TraitObject(_)) | None => field_ty,
+                Some(pointee) => pointee,
+            };
+            let ty_str = ty.to_token_stream().to_string();
+            if let Some((_, other)) = source_types
+                .iter()
+                .find(|(other_ty, _)| *other_ty == ty_str)
+            {
+                panic!(
+                    "`{ident}` and `{other}` both generate `From<{ty_str}>`, \
+                    just one variant can use `from` for each type"
+                );
+            }
+            source_types.push((ty_str, ident));
+
+            let member = variant.transparent_member();
+            quote! {
+                impl #impl_generics ::std::convert::From<#ty> for #name #ty_generics #where_clause {
+                    fn from(inner: #ty) -> Self {
+                        Self::#ident {
+                            #member: ::std::convert::From::from(inner),
+                        }
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Checks the placeholders of the message against the fields of the variant and
+/// rewrites the tuple ones (`{0}`) to `{_0}`, so every placeholder is a named
+/// argument of `write!`. Returns the format string and the index of each field used
+/// with the `std::fmt` trait it's formatted with.
+fn message_format(message: &str, variant: &ErrorVariant) -> (String, Vec<(usize, Ident)>) {
+    let mut format = String::new();
+    let mut fields = Vec::new();
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        format.push(c);
+        if c != '{' {
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            format.push(chars.next().unwrap());
+            continue;
+        }
+
+        let mut placeholder = String::new();
+        for c in chars.by_ref() {
+            if c == '}' {
+                break;
+            }
+            placeholder.push(c);
+        }
+
+        let (arg, spec) = match placeholder.split_once(':') {
+            Some((arg, spec)) => (arg.trim(), Some(spec)),
+            None => (placeholder.trim(), None),
+        };
+        let field = match (&variant.fields, arg.parse::<usize>()) {
+            (Fields::Unnamed(fields), Ok(index)) if index < fields.unnamed.len() => Some(index),
+            (Fields::Named(fields), Err(_)) => fields
+                .named
+                .iter()
+                .position(|field| field.ident.as_ref().unwrap() == arg),
+            _ => None,
+        };
+        let Some(field) = field else {
+            panic!(
+                "Placeholder `{{{arg}}}` in the message of `{}` doesn't match any field",
+                variant.ident
+            );
+        };
+
+        // Indexes aren't valid names of format arguments
+        match arg.parse::<usize>() {
+            Ok(index) => format.push_str(&format!("_{index}")),
+            Err(_) => format.push_str(arg),
+        }
+        if let Some(spec) = spec {
+            format.push(':');
+            format.push_str(spec);
+        }
+        format.push('}');
+
+        let format_trait = format_ident!("{}", format_trait(spec.unwrap_or_default()));
+        if !fields.contains(&(field, format_trait.clone())) {
+            fields.push((field, format_trait));
+        }
+    }
+
+    (format, fields)
+}
+
+/// `std::fmt` trait required by a format spec, `Debug` for `{:?}` or `{:#x?}`.
+fn format_trait(spec: &str) -> &'static str {
+    if spec.ends_with('?') {
+        return "Debug";
+    }
+
+    match spec.chars().last() {
+        Some('x') => "LowerHex",
+        Some('X') => "UpperHex",
+        Some('o') => "Octal",
+        Some('b') => "Binary",
+        Some('e') => "LowerExp",
+        Some('E') => "UpperExp",
+        Some('p') => "Pointer",
+        _ => "Display",
     }
 }
+
+/// Checks if the field has the `#[error_kind(FLAG)]` attribute.
+fn has_flag(field: &Field, flag: &str) -> bool {
+    error_kind_args(&field.attrs)
+        .iter()
+        .flatten()
+        .any(|arg| arg.is_flag(flag))
+}
+
+/// Returns `T` when the type is `Box<T>`, `Arc<T>` or `Rc<T>`.
+fn smart_pointee(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let segment = type_path.path.segments.last()?;
+    if !["Box", "Arc", "Rc"].contains(&segment.ident.to_string().as_str()) {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(pointee) => Some(pointee),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    a.to_token_stream().to_string() == b.to_token_stream().to_string()
+}
+
+fn uses_type_params(ty: &Type, type_params: &[Ident]) -> bool {
+    fn walk(tokens: proc_macro2::TokenStream, type_params: &[Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => type_params.contains(&ident),
+            TokenTree::Group(group) => walk(group.stream(), type_params),
+            _ => false,
+        })
+    }
+
+    walk(ty.to_token_stream(), type_params)
+}
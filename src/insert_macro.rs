@@ -1,17 +1,27 @@
 use proc_macro::TokenStream;
+use proc_macro2::Span;
 use quote::quote;
 use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input, Expr, LitStr, Result,
 };
 
+use crate::{
+    config::setting_tracking,
+    location_comment::with_location,
+    schema::{check_columns, SCHEMA_VAR},
+    tenant::{TenantScope, TENANT_COLUMN_VAR},
+};
+
 struct InsertQueryInput {
+    tenant: TenantScope,
     table_name: Expr,
     cols: Vec<LitStr>,
 }
 
 impl Parse for InsertQueryInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        let tenant = TenantScope::parse(input)?;
         let table_name = input.parse()?;
         input.parse::<syn::Token![,]>()?;
         let mut cols = Vec::new();
@@ -21,12 +31,22 @@ impl Parse for InsertQueryInput {
                 input.parse::<syn::Token![,]>()?;
             }
         }
-        Ok(InsertQueryInput { table_name, cols })
+        Ok(InsertQueryInput {
+            tenant,
+            table_name,
+            cols,
+        })
     }
 }
 
 pub fn insert_stmt_macro(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as InsertQueryInput);
+    let mut input = parse_macro_input!(input as InsertQueryInput);
+    if let Some(column) = &input.tenant.column {
+        if !input.cols.iter().any(|col| &col.value() == column) {
+            input.cols.push(LitStr::new(column, Span::call_site()));
+        }
+    }
+
     let table_name = &input.table_name;
     let cols = &input.cols;
 
@@ -36,6 +56,11 @@ pub fn insert_stmt_macro(input: TokenStream) -> TokenStream {
     let col_names = col_names.join(", ");
     let col_values = col_values.join(", ");
 
+    let schema_tracking = match check_columns(table_name, cols) {
+        Ok(tracking) => tracking,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let expanded = quote! {
         format!(
             "INSERT INTO {} ({}) VALUES ({})",
@@ -45,5 +70,14 @@ pub fn insert_stmt_macro(input: TokenStream) -> TokenStream {
         )
     };
 
-    TokenStream::from(expanded)
+    let expanded = with_location(expanded);
+    let settings_tracking = setting_tracking(&[SCHEMA_VAR, TENANT_COLUMN_VAR]);
+
+    TokenStream::from(quote! {
+        {
+            #settings_tracking
+            #schema_tracking
+            #expanded
+        }
+    })
 }
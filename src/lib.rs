@@ -10,14 +10,23 @@ use syn::{
     parse_macro_input, Data, DeriveInput, Fields, Token,
 };
 
+mod config;
+mod create_table_macro;
+mod db_error_macro;
 mod delete_macro;
+mod error_kind_attr;
 mod error_kind_macro;
 mod impl_kind_macro;
 mod insert_macro;
+mod location_comment;
+mod naming;
 mod partial_object;
 mod partial_struct;
 mod reading_option;
+mod schema;
 mod select_macro;
+mod tenant;
+mod union_macro;
 mod update_macro;
 
 /// Create a kind method for struct
@@ -54,24 +63,1344 @@ mod update_macro;
 /// assert_eq!(ServiceError::Cache(CacheError::Missing).kind(), ErrorType::B);
 /// assert_eq!(ServiceError::Db.kind(), ErrorType::C);
 /// ```
+///
+/// Generic enums are supported. A transparent variant over a generic type needs a
+/// trait with the `kind` method, set with `kind_trait`, which the derive implements too:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Invalid,
+/// }
+///
+/// trait HasKind {
+///     fn kind(&self) -> ErrorType;
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType, kind_trait = "HasKind")]
+/// enum DbError {
+///     #[error_kind(ErrorType, NotFound)]
+///     Missing,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, kind_trait = "HasKind")]
+/// enum RepoError<'a, E: std::fmt::Debug> {
+///     #[error_kind(transparent)]
+///     Db(E),
+///
+///     #[error_kind(ErrorType, Invalid)]
+///     Parse(&'a str),
+/// }
+///
+/// let err: RepoError<DbError> = RepoError::Db(DbError::Missing);
+/// assert_eq!(err.kind(), ErrorType::NotFound);
+/// assert_eq!(RepoError::<DbError>::Parse("x").kind(), ErrorType::Invalid);
+/// ```
+///
+/// `status = CODE` in the variants generates a `status_code` method. The enum
+/// attribute can set the default status of the variants without it:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, status = 500)]
+/// enum CacheError {
+///     #[error_kind(ErrorType, NotFound, status = 404)]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Internal)]
+///     Poisoned,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     Cache(CacheError),
+///
+///     #[error_kind(ErrorType, Internal, status = 503)]
+///     Unavailable,
+/// }
+///
+/// assert_eq!(ServiceError::Cache(CacheError::Missing).status_code(), 404);
+/// assert_eq!(ServiceError::Cache(CacheError::Poisoned).status_code(), 500);
+/// assert_eq!(ServiceError::Unavailable.status_code(), 503);
+/// ```
+///
+/// The status must be between 100 and 599:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// enum ErrorType {
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum CacheError {
+///     #[error_kind(ErrorType, Internal, status = 1000)]
+///     Poisoned,
+/// }
+/// ```
+///
+/// And every variant needs one when the enum doesn't set a default:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// enum ErrorType {
+///     NotFound,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum CacheError {
+///     #[error_kind(ErrorType, NotFound, status = 404)]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Internal)]
+///     Poisoned,
+/// }
+/// ```
+///
+/// `kind_trait` only bounds `kind`, so a transparent variant over a generic type
+/// sets its own status, and likewise its `code`, `grpc`, `level` or `metric`:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// enum ErrorType {
+///     Internal,
+/// }
+///
+/// trait HasKind {
+///     fn kind(&self) -> ErrorType;
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, kind_trait = "HasKind")]
+/// enum DbError {
+///     #[error_kind(ErrorType, Internal, status = 500)]
+///     Timeout,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, kind_trait = "HasKind")]
+/// enum RepoError<E> {
+///     #[error_kind(transparent, status = 503)]
+///     Db(E),
+/// }
+///
+/// assert_eq!(RepoError::Db(DbError::Timeout).status_code(), 503);
+/// ```
+///
+/// Instead of taking it from the wrapped error:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// enum ErrorType {
+///     Internal,
+/// }
+///
+/// trait HasKind {
+///     fn kind(&self) -> ErrorType;
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, kind_trait = "HasKind")]
+/// enum RepoError<E> {
+///     #[error_kind(transparent)]
+///     Db(E),
+///
+///     #[error_kind(ErrorType, Internal, status = 500)]
+///     Poisoned,
+/// }
+/// ```
+///
+/// `message = "..."` generates `Display`. The message can use the named fields
+/// (`{id}`) and the tuple fields (`{0}`), and transparent variants use the
+/// `Display` of the wrapped error:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum CacheError {
+///     #[error_kind(ErrorType, Internal, message = "cache poisoned")]
+///     Poisoned,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum ServiceError {
+///     #[error_kind(ErrorType, NotFound, message = "user {id} not found")]
+///     UserNotFound { id: u32 },
+///
+///     #[error_kind(ErrorType, Internal, message = "query failed ({1}): {0:?}")]
+///     Query(String, u16),
+///
+///     #[error_kind(ErrorType, NotFound, message = "missing file {f}")]
+///     MissingFile { f: String },
+///
+///     #[error_kind(transparent)]
+///     Cache(CacheError),
+/// }
+///
+/// assert_eq!(ServiceError::UserNotFound { id: 7 }.to_string(), "user 7 not found");
+/// assert_eq!(
+///     ServiceError::MissingFile { f: "a.txt".to_string() }.to_string(),
+///     "missing file a.txt"
+/// );
+/// assert_eq!(
+///     ServiceError::Query("SELECT".to_string(), 1064).to_string(),
+///     "query failed (1064): \"SELECT\""
+/// );
+/// assert_eq!(ServiceError::Cache(CacheError::Poisoned).to_string(), "cache poisoned");
+/// ```
+///
+/// The fields of generic types used in the message get the bound of their format:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Invalid,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum ParseError<E, P> {
+///     #[error_kind(ErrorType, Invalid, message = "invalid value {0} at {1:?}")]
+///     Invalid(E, P),
+/// }
+///
+/// let err: ParseError<u8, (u32, u32)> = ParseError::Invalid(7, (1, 2));
+/// assert_eq!(err.to_string(), "invalid value 7 at (1, 2)");
+/// ```
+///
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum ServiceError {
+///     #[error_kind(ErrorType, NotFound, message = "user {user_id} not found")]
+///     UserNotFound { id: u32 },
+/// }
+/// ```
+///
+/// `impl_error` in the enum attribute generates `std::error::Error`. Its `source`
+/// is the wrapped error of the transparent variants, or the field marked with
+/// `#[error_kind(source)]`:
+/// ```
+/// use std::error::Error;
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Invalid,
+///     Internal,
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType, impl_error)]
+/// enum CacheError {
+///     #[error_kind(ErrorType, Invalid, message = "invalid entry")]
+///     Parse(#[error_kind(source)] std::num::ParseIntError),
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType, impl_error)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     Cache(CacheError),
+///
+///     #[error_kind(ErrorType, Internal, message = "unavailable")]
+///     Unavailable,
+/// }
+///
+/// let parse_error = "x".parse::<u8>().unwrap_err();
+/// let err = ServiceError::Cache(CacheError::Parse(parse_error.clone()));
+/// let cache_error = err.source().unwrap();
+/// assert_eq!(cache_error.to_string(), "invalid entry");
+/// assert_eq!(cache_error.source().unwrap().to_string(), parse_error.to_string());
+/// assert!(ServiceError::Unavailable.source().is_none());
+/// ```
+///
+/// `#[error_kind(transparent, from)]` generates `From` for the wrapped error, so
+/// it can be converted with `?`:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Invalid,
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum CacheError {
+///     #[error_kind(ErrorType, Invalid)]
+///     Missing,
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum ServiceError {
+///     #[error_kind(transparent, from)]
+///     Cache(CacheError),
+/// }
+///
+/// fn get() -> Result<(), ServiceError> {
+///     Err(CacheError::Missing)?
+/// }
+///
+/// assert_eq!(get().unwrap_err().kind(), ErrorType::Invalid);
+/// ```
+///
+/// Just one variant can use `from` for each type, and a `Box`, `Arc` or `Rc`
+/// counts as the type it wraps (see `tests/ui/from_duplicate_type.rs`).
+///
+/// A transparent variant can have named fields, with the wrapped error marked with
+/// `#[error_kind(source)]`, and `Box`, `Arc` and `Rc` are dereferenced:
+/// ```
+/// use std::error::Error;
+/// use std::sync::Arc;
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Invalid,
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType, impl_error)]
+/// enum CacheError {
+///     #[error_kind(ErrorType, Invalid, message = "missing entry")]
+///     Missing,
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType, impl_error)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     Cache {
+///         #[error_kind(source)]
+///         source: Box<CacheError>,
+///         key: String,
+///     },
+///
+///     #[error_kind(transparent, from)]
+///     Shared(Arc<CacheError>),
+///
+///     #[error_kind(ErrorType, Invalid, message = "invalid key")]
+///     Key,
+/// }
+///
+/// let err = ServiceError::Cache {
+///     source: Box::new(CacheError::Missing),
+///     key: "user".to_string(),
+/// };
+/// assert_eq!(err.kind(), ErrorType::Invalid);
+/// assert_eq!(err.to_string(), "missing entry");
+/// assert!(err.source().unwrap().is::<CacheError>());
+///
+/// let err = ServiceError::from(CacheError::Missing);
+/// assert_eq!(err.kind(), ErrorType::Invalid);
+/// ```
+///
+/// `default = VARIANT` in the enum attribute sets the kind of the variants
+/// without attribute:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, default = Internal)]
+/// enum ServiceError {
+///     #[error_kind(ErrorType, NotFound)]
+///     Missing,
+///     Db(String),
+///     Io { path: String },
+/// }
+///
+/// assert_eq!(ServiceError::Missing.kind(), ErrorType::NotFound);
+/// assert_eq!(ServiceError::Db("timeout".to_string()).kind(), ErrorType::Internal);
+/// ```
+///
+/// `generate = NAME` generates the kind enum, with a variant for each kind used in
+/// the error variants, which then just need the kind variant:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(generate = ServiceErrorKind, default = Internal)]
+/// enum ServiceError {
+///     #[error_kind(NotFound)]
+///     UserNotFound,
+///     #[error_kind(NotFound)]
+///     GroupNotFound,
+///     #[error_kind(Conflict)]
+///     Duplicated,
+///     Db(String),
+/// }
+///
+/// assert_eq!(ServiceError::Duplicated.kind(), ServiceErrorKind::Conflict);
+/// assert_eq!(
+///     ServiceErrorKind::ALL,
+///     &[ServiceErrorKind::NotFound, ServiceErrorKind::Conflict, ServiceErrorKind::Internal]
+/// );
+/// assert_eq!(ServiceErrorKind::NotFound.as_str(), "NotFound");
+/// assert_eq!("Conflict".parse(), Ok(ServiceErrorKind::Conflict));
+/// ```
+///
+/// The generated items don't clash with a `Result` alias next to the error:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(generate = ServiceErrorKind)]
+/// enum ServiceError {
+///     #[error_kind(NotFound)]
+///     UserNotFound,
+/// }
+///
+/// type Result<T> = std::result::Result<T, ServiceError>;
+///
+/// fn find_user() -> Result<()> {
+///     Err(ServiceError::UserNotFound)
+/// }
+///
+/// assert_eq!(find_user().unwrap_err().kind(), "NotFound".parse().unwrap());
+/// ```
+///
+/// The generated items are documented, so they can be used in crates which deny
+/// `missing_docs`:
+/// ```
+/// #![deny(missing_docs)]
+/// //! Errors of the service.
+///
+/// use web_proc_macros::ErrorKind;
+///
+/// /// Errors of the service.
+/// #[derive(ErrorKind)]
+/// #[error_kind(generate = ServiceErrorKind, status = 500, variant_kinds)]
+/// #[error_kind(problem_details(base_uri = "/errors/"))]
+/// pub enum ServiceError {
+///     /// The user doesn't exist.
+///     #[error_kind(NotFound, status = 404, message = "user not found")]
+///     UserNotFound,
+/// }
+///
+/// # fn main() {}
+/// ```
+///
+/// The kind enum can't be empty, so a variant must have a kind:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(generate = ServiceErrorKind)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     Io(std::io::Error),
+/// }
+/// ```
+///
+/// When the wrapped error of a transparent variant uses another kind type, `map = FN`
+/// or `map(From)` converts its kind:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum DbErrorKind {
+///     Duplicated,
+///     Connection,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ServiceErrorKind {
+///     Conflict,
+///     Internal,
+/// }
+///
+/// impl From<DbErrorKind> for ServiceErrorKind {
+///     fn from(kind: DbErrorKind) -> Self {
+///         match kind {
+///             DbErrorKind::Duplicated => ServiceErrorKind::Conflict,
+///             DbErrorKind::Connection => ServiceErrorKind::Internal,
+///         }
+///     }
+/// }
+///
+/// fn always_internal(_: DbErrorKind) -> ServiceErrorKind {
+///     ServiceErrorKind::Internal
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(DbErrorKind)]
+/// enum DbError {
+///     #[error_kind(DbErrorKind, Duplicated)]
+///     Duplicated,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ServiceErrorKind)]
+/// enum ServiceError {
+///     #[error_kind(transparent, map(From))]
+///     Db(DbError),
+///
+///     #[error_kind(transparent, map = always_internal)]
+///     Cache(DbError),
+/// }
+///
+/// assert_eq!(ServiceError::Db(DbError::Duplicated).kind(), ServiceErrorKind::Conflict);
+/// assert_eq!(ServiceError::Cache(DbError::Duplicated).kind(), ServiceErrorKind::Internal);
+/// ```
+///
+/// Without `map`, a wrapped error of another kind type fails the build at the
+/// variant, with "the `kind` of the error wrapped by `Db` isn't a `ServiceErrorKind`"
+/// (see `tests/ui/transparent_kind_without_map.rs`):
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum DbErrorKind {
+///     Duplicated,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ServiceErrorKind {
+///     Conflict,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(DbErrorKind)]
+/// enum DbError {
+///     #[error_kind(DbErrorKind, Duplicated)]
+///     Duplicated,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ServiceErrorKind)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     Db(DbError),
+/// }
+/// ```
+///
+/// Each `#[error_kind(TYPE)]` in the enum adds a kind with its own accessor, the
+/// snake case type name unless it sets `accessor = NAME`. The variants set each kind
+/// in its own attribute, or take it from `default` or the wrapped error:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum Severity {
+///     Low,
+///     High,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum RetryPolicy {
+///     Never,
+///     Backoff,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// #[error_kind(Severity, accessor = severity_level, default = Low)]
+/// #[error_kind(RetryPolicy)]
+/// enum DbError {
+///     #[error_kind(ErrorType, NotFound)]
+///     #[error_kind(RetryPolicy, Never)]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Conflict)]
+///     #[error_kind(Severity, High)]
+///     #[error_kind(RetryPolicy, Backoff)]
+///     Locked,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// #[error_kind(Severity, accessor = severity_level)]
+/// #[error_kind(RetryPolicy)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     #[error_kind(RetryPolicy, Never)]
+///     Db(DbError),
+/// }
+///
+/// assert_eq!(DbError::Missing.severity_level(), Severity::Low);
+/// assert_eq!(DbError::Locked.retry_policy(), RetryPolicy::Backoff);
+///
+/// let err = ServiceError::Db(DbError::Locked);
+/// assert_eq!(err.kind(), ErrorType::Conflict);
+/// assert_eq!(err.severity_level(), Severity::High);
+/// assert_eq!(err.retry_policy(), RetryPolicy::Never);
+/// ```
+///
+/// Every variant must set each kind, unless the enum has a `default` for it:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum Severity {
+///     Low,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// #[error_kind(Severity)]
+/// enum DbError {
+///     #[error_kind(ErrorType, NotFound)]
+///     Missing,
+/// }
+/// ```
+///
+/// `problem_details(base_uri = "...")` generates `to_problem_details()`, which
+/// returns an RFC 7807 problem details struct serialized with serde (it requires the
+/// `serde` crate). The type and title come from the kind, which must implement
+/// `Debug`, the status from `status` and the detail from `Display`. The fields
+/// marked with `#[error_kind(extension)]` are added as extension members:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType, problem_details(base_uri = "https://errors.example.com/"))]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound, status = 404, message = "user {id} not found")]
+///     NotFound { id: u32 },
+///
+///     #[error_kind(ErrorType, Conflict, status = 409, message = "user {id} is locked")]
+///     Locked {
+///         id: u32,
+///         #[error_kind(extension)]
+///         retry_after: u64,
+///     },
+/// }
+///
+/// let problem = UserError::NotFound { id: 1 }.to_problem_details();
+/// assert_eq!(problem.problem_type, "https://errors.example.com/not-found");
+/// assert_eq!(problem.title, "Not found");
+/// assert_eq!(problem.status, 404);
+///
+/// let problem = UserError::Locked { id: 1, retry_after: 30 }.to_problem_details();
+/// assert_eq!(
+///     serde_json::to_value(&problem).unwrap(),
+///     serde_json::json!({
+///         "type": "https://errors.example.com/conflict",
+///         "title": "Conflict",
+///         "status": 409,
+///         "detail": "user 1 is locked",
+///         "retry_after": 30,
+///     })
+/// );
+/// ```
+///
+/// The struct borrows the error with a lifetime of its own, so the enum can have
+/// lifetimes with any name:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, problem_details(base_uri = "/errors/"))]
+/// enum FileError<'problem> {
+///     #[error_kind(ErrorType, NotFound, status = 404, message = "{path} not found")]
+///     NotFound {
+///         path: &'problem str,
+///         #[error_kind(extension)]
+///         map: &'problem str,
+///     },
+/// }
+///
+/// let problem = FileError::NotFound { path: "a.txt", map: "files" }.to_problem_details();
+/// assert_eq!(serde_json::to_value(&problem).unwrap()["map"], "files");
+/// ```
+///
+/// `code = "..."` sets a stable code of the variant, returned by `code()`. With
+/// `code` or `code_prefix = "..."` in the enum, the variants without it use the prefix
+/// and their name in screaming snake case. Transparent variants return the code of
+/// the wrapped error, and `CODES` lists the codes of the other variants:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, code_prefix = "USER_")]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound, code = "USER_NOT_FOUND")]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Conflict)]
+///     EmailTaken,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, code)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     User(UserError),
+///
+///     #[error_kind(ErrorType, Conflict)]
+///     Locked,
+/// }
+///
+/// assert_eq!(UserError::Missing.code(), "USER_NOT_FOUND");
+/// assert_eq!(UserError::EmailTaken.code(), "USER_EMAIL_TAKEN");
+/// assert_eq!(UserError::CODES, &["USER_NOT_FOUND", "USER_EMAIL_TAKEN"]);
+/// assert_eq!(ServiceError::User(UserError::Missing).code(), "USER_NOT_FOUND");
+/// assert_eq!(ServiceError::Locked.code(), "LOCKED");
+/// ```
+///
+/// The codes must be unique:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, code)]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound, code = "NOT_FOUND")]
+///     Missing,
+///
+///     #[error_kind(ErrorType, NotFound)]
+///     NotFound,
+/// }
+/// ```
+///
+/// Without `code` or `code_prefix` in the enum, a variant without `code` is an
+/// error when others set it:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound, code = "USER_NOT_FOUND")]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Conflict)]
+///     EmailTaken,
+/// }
+/// ```
+///
+/// The fields marked with `#[error_kind(sensitive)]` are printed as `[REDACTED]`
+/// by the generated `Debug`, which replaces `#[derive(Debug)]`, by the message and
+/// by the problem details:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Unauthorized,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum AuthError {
+///     #[error_kind(ErrorType, Unauthorized, message = "invalid token {token} for {email}")]
+///     InvalidToken {
+///         email: String,
+///         #[error_kind(sensitive)]
+///         token: String,
+///     },
+///
+///     #[error_kind(ErrorType, Unauthorized, message = "invalid password for {0}")]
+///     InvalidPassword(String, #[error_kind(sensitive)] String),
+///
+///     #[error_kind(ErrorType, Unauthorized, message = "expired token")]
+///     Expired {
+///         #[error_kind(sensitive)]
+///         token: String,
+///         f: u8,
+///     },
+/// }
+///
+/// let err = AuthError::InvalidToken {
+///     email: "user@example.com".to_string(),
+///     token: "secret".to_string(),
+/// };
+/// assert_eq!(
+///     format!("{err:?}"),
+///     r#"InvalidToken { email: "user@example.com", token: [REDACTED] }"#
+/// );
+/// assert_eq!(err.to_string(), "invalid token [REDACTED] for user@example.com");
+///
+/// let err = AuthError::InvalidPassword("user@example.com".to_string(), "secret".to_string());
+/// assert_eq!(format!("{err:?}"), r#"InvalidPassword("user@example.com", [REDACTED])"#);
+///
+/// let err = AuthError::Expired { token: "secret".to_string(), f: 1 };
+/// assert_eq!(format!("{err:?}"), "Expired { token: [REDACTED], f: 1 }");
+/// ```
+///
+/// `grpc = CODE` sets the canonical gRPC status code of the variant, returned as
+/// its number by `grpc_code()`. The enum can map kinds to codes with
+/// `grpc(KIND = CODE, ...)` and set a default with `grpc = CODE`, or just enable
+/// `grpc_code()` for transparent variants with `grpc`:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, grpc(NotFound = NotFound, Conflict = AlreadyExists), grpc = Internal)]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound)]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Conflict)]
+///     EmailTaken,
+///
+///     #[error_kind(ErrorType, Conflict, grpc = FailedPrecondition)]
+///     Locked,
+///
+///     #[error_kind(ErrorType, Internal)]
+///     Db,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, grpc)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     User(UserError),
+/// }
+///
+/// assert_eq!(UserError::Missing.grpc_code(), 5);
+/// assert_eq!(UserError::EmailTaken.grpc_code(), 6);
+/// assert_eq!(UserError::Locked.grpc_code(), 9);
+/// assert_eq!(UserError::Db.grpc_code(), 13);
+/// assert_eq!(ServiceError::User(UserError::Missing).grpc_code(), 5);
+/// ```
+///
+/// `exhaustive` checks that the variants use every kind of the kind type, so the
+/// build fails when a kind is added and no error uses it. The kinds which are
+/// intentionally unused, or just used by the wrapped errors, go in `allow_unused(...)`:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, exhaustive, allow_unused(Internal))]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound)]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Conflict)]
+///     EmailTaken,
+/// }
+///
+/// assert_eq!(UserError::Missing.kind(), ErrorType::NotFound);
+/// ```
+///
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, exhaustive)]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound)]
+///     Missing,
+/// }
+/// ```
+///
+/// `constructors` generates a snake case constructor for each variant, whose
+/// arguments take `impl Into<T>`. The field marked with `#[error_kind(location)]`,
+/// a `&'static Location<'static>`, is filled with the location of the caller:
+/// ```
+/// use std::panic::Location;
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Invalid,
+/// }
+///
+/// #[derive(Debug, ErrorKind)]
+/// #[error_kind(ErrorType, constructors)]
+/// enum ServiceError {
+///     #[error_kind(ErrorType, Invalid)]
+///     ErrorC {
+///         content: String,
+///         line: u32,
+///         #[error_kind(location)]
+///         location: &'static Location<'static>,
+///     },
+///
+///     #[error_kind(ErrorType, Invalid)]
+///     Parse(String, u64),
+///
+///     #[error_kind(ErrorType, Invalid)]
+///     Empty,
+/// }
+///
+/// let err = ServiceError::error_c("unexpected token", 10u8);
+/// let ServiceError::ErrorC { content, line, location } = err else {
+///     unreachable!();
+/// };
+/// assert_eq!(content, "unexpected token");
+/// assert_eq!(line, 10);
+/// assert_eq!(location.line(), line!() - 6);
+///
+/// assert!(matches!(ServiceError::parse("x", 1u32), ServiceError::Parse(_, 1)));
+/// assert!(matches!(ServiceError::empty(), ServiceError::Empty));
+/// ```
+///
+/// Constructors named after a keyword are raw identifiers:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// enum ErrorType {
+///     Invalid,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, constructors)]
+/// enum ParseError {
+///     #[error_kind(ErrorType, Invalid)]
+///     Type(String),
+///
+///     #[error_kind(ErrorType, Invalid)]
+///     Match,
+/// }
+///
+/// assert!(matches!(ParseError::r#type("u8"), ParseError::Type(_)));
+/// assert!(matches!(ParseError::r#match(), ParseError::Match));
+/// ```
+///
+/// A keyword which can't be raw is rejected:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// enum ErrorType {
+///     Invalid,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, constructors)]
+/// enum ModuleError {
+///     #[error_kind(ErrorType, Invalid)]
+///     Crate,
+/// }
+/// ```
+///
+/// And so is a constructor named like a generated method:
+/// ```compile_fail
+/// use web_proc_macros::ErrorKind;
+///
+/// enum ErrorType {
+///     Invalid,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, constructors)]
+/// enum ParseError {
+///     #[error_kind(ErrorType, Invalid)]
+///     Kind,
+/// }
+/// ```
+///
+/// `level = LEVEL` generates `log_level()` and `metric = "..."` generates
+/// `metric_label()`, with the enum setting the defaults. With `metric` in the enum,
+/// the variants without it use their kind in snake case:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, level = error, metric)]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound, level = warn)]
+///     Missing,
+///
+///     #[error_kind(ErrorType, Internal, metric = "user_db")]
+///     Db,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, level = error, metric = "service")]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     User(UserError),
+///
+///     #[error_kind(ErrorType, Internal)]
+///     Unavailable,
+/// }
+///
+/// assert_eq!(UserError::Missing.log_level(), "warn");
+/// assert_eq!(UserError::Missing.metric_label(), "not_found");
+/// assert_eq!(UserError::Db.log_level(), "error");
+/// assert_eq!(UserError::Db.metric_label(), "user_db");
+/// assert_eq!(ServiceError::User(UserError::Missing).log_level(), "warn");
+/// assert_eq!(ServiceError::Unavailable.metric_label(), "service");
+/// ```
+///
+/// `kind()` is a `const fn` when no variant takes the kind from a wrapped error.
+/// With `variant_kinds`, `VARIANT_KINDS` and `kind_of_variant` give the kind of
+/// each variant by name, without the transparent variants. They are associated
+/// items, so generic enums need the type parameters, `MyError::<T>::VARIANT_KINDS`:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     NotFound,
+///     Conflict,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, variant_kinds)]
+/// enum UserError {
+///     #[error_kind(ErrorType, NotFound)]
+///     Missing { id: u32 },
+///
+///     #[error_kind(ErrorType, Conflict)]
+///     EmailTaken(String),
+/// }
+///
+/// const MISSING: ErrorType = UserError::Missing { id: 1 }.kind();
+/// assert_eq!(MISSING, ErrorType::NotFound);
+///
+/// assert_eq!(
+///     UserError::VARIANT_KINDS,
+///     &[("Missing", ErrorType::NotFound), ("EmailTaken", ErrorType::Conflict)]
+/// );
+/// assert_eq!(UserError::kind_of_variant("EmailTaken"), Some(ErrorType::Conflict));
+/// assert_eq!(UserError::kind_of_variant("Unknown"), None);
+/// ```
+///
+/// `is_retryable()`, `is_client_error()` and `is_server_error()` are generated with
+/// the enum `classification` flag or when a variant sets `retryable` or
+/// `client_error`. Without the flags they come from the status: 408, 429, 502, 503
+/// and 504 are retryable, and a variant without status is a server error:
+/// ```
+/// use web_proc_macros::ErrorKind;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Invalid,
+///     Unavailable,
+///     Internal,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum JobError {
+///     #[error_kind(ErrorType, Invalid, client_error)]
+///     InvalidPayload,
+///
+///     #[error_kind(ErrorType, Unavailable, retryable)]
+///     Busy,
+///
+///     #[error_kind(ErrorType, Internal)]
+///     Crashed,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum ServiceError {
+///     #[error_kind(transparent)]
+///     Job(JobError),
+///
+///     #[error_kind(ErrorType, Unavailable, retryable)]
+///     PoolTimeout,
+/// }
+///
+/// #[derive(ErrorKind)]
+/// #[error_kind(ErrorType, status = 500, classification)]
+/// enum HttpError {
+///     #[error_kind(ErrorType, Unavailable, status = 503)]
+///     Unavailable,
+///
+///     #[error_kind(ErrorType, Invalid, status = 422)]
+///     Invalid,
+///
+///     #[error_kind(ErrorType, Internal)]
+///     Internal,
+/// }
+///
+/// assert!(JobError::InvalidPayload.is_client_error());
+/// assert!(!JobError::InvalidPayload.is_server_error());
+/// assert!(JobError::Busy.is_retryable());
+/// assert!(JobError::Crashed.is_server_error());
+/// assert!(!JobError::Crashed.is_retryable());
+/// assert!(ServiceError::Job(JobError::Busy).is_retryable());
+///
+/// assert!(HttpError::Unavailable.is_retryable());
+/// assert!(HttpError::Invalid.is_client_error());
+/// assert!(HttpError::Internal.is_server_error());
+/// ```
 #[proc_macro_derive(ErrorKind, attributes(error_kind))]
 pub fn error_kind(input: TokenStream) -> TokenStream {
     error_kind_macro::error_kind_macro(input)
 }
 
+/// Create a kind method for enum, the kind type is taken from the variants.
+/// # Examples
+/// ```
+/// use web_proc_macros::ImplKind;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum MyErrorKind {
+///     Kind1,
+///     Kind2,
+/// }
+///
+/// #[derive(ImplKind)]
+/// pub enum MyError<'a, T: Clone> {
+///     #[error_kind(MyErrorKind, Kind1)]
+///     ErrorA(&'a str),
+///     #[error_kind(MyErrorKind, Kind2)]
+///     ErrorB { value: T },
+/// }
+///
+/// assert_eq!(MyError::<u8>::ErrorA("err").kind(), MyErrorKind::Kind1);
+/// assert_eq!(MyError::ErrorB { value: 1 }.kind(), MyErrorKind::Kind2);
+/// ```
+///
+/// The enum attribute can set the default kind of the variants without attribute:
+/// ```
+/// use web_proc_macros::ImplKind;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum MyErrorKind {
+///     Kind1,
+///     Kind2,
+/// }
+///
+/// #[derive(ImplKind)]
+/// #[error_kind(MyErrorKind, default = Kind2)]
+/// pub enum MyError {
+///     #[error_kind(MyErrorKind, Kind1)]
+///     ErrorA,
+///     ErrorB,
+/// }
+///
+/// assert_eq!(MyError::ErrorA.kind(), MyErrorKind::Kind1);
+/// assert_eq!(MyError::ErrorB.kind(), MyErrorKind::Kind2);
+/// ```
+///
+/// Stacked attributes of other types add an accessor for each type, named in
+/// snake case:
+/// ```
+/// use web_proc_macros::ImplKind;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum MyErrorKind {
+///     Kind1,
+///     Kind2,
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum Severity {
+///     Low,
+///     High,
+/// }
+///
+/// #[derive(ImplKind)]
+/// pub enum MyError {
+///     #[error_kind(MyErrorKind, Kind1)]
+///     #[error_kind(Severity, Low)]
+///     ErrorA,
+///     #[error_kind(MyErrorKind, Kind2)]
+///     #[error_kind(Severity, High)]
+///     ErrorB,
+/// }
+///
+/// assert_eq!(MyError::ErrorA.kind(), MyErrorKind::Kind1);
+/// assert_eq!(MyError::ErrorB.severity(), Severity::High);
+/// ```
+///
+/// The other options of `ErrorKind` are rejected:
+/// ```compile_fail
+/// use web_proc_macros::ImplKind;
+///
+/// pub enum MyErrorKind {
+///     Kind1,
+/// }
+///
+/// #[derive(ImplKind)]
+/// pub enum MyError {
+///     #[error_kind(MyErrorKind, Kind1, status = 404)]
+///     ErrorA,
+/// }
+/// ```
 #[proc_macro_derive(ImplKind, attributes(error_kind))]
 pub fn impl_kind(input: TokenStream) -> TokenStream {
     impl_kind_macro::impl_kind_macro(input)
 }
 
+/// Generates `from_db_code`, which returns the variant of a database driver
+/// error from its mysql code or its SQLSTATE. The mysql codes are checked first.
+/// # Examples
+/// ```
+/// use web_proc_macros::{DbError, ErrorKind};
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum ErrorType {
+///     Conflict,
+///     Invalid,
+/// }
+///
+/// #[derive(Debug, PartialEq, DbError, ErrorKind)]
+/// #[error_kind(ErrorType)]
+/// enum QueryError {
+///     #[db_error(mysql = 1062, sqlstate = "23505")]
+///     #[error_kind(ErrorType, Conflict)]
+///     Duplicated,
+///
+///     #[db_error(mysql = 1452, mysql = 1451, sqlstate = "23503")]
+///     #[error_kind(ErrorType, Invalid)]
+///     ForeignKey,
+/// }
+///
+/// assert_eq!(QueryError::from_db_code(1062, Some("23000")), Some(QueryError::Duplicated));
+/// assert_eq!(QueryError::from_db_code(0, Some("23505")), Some(QueryError::Duplicated));
+/// assert_eq!(
+///     QueryError::from_db_code(1451, None).map(|err| err.kind()),
+///     Some(ErrorType::Invalid)
+/// );
+/// assert_eq!(QueryError::from_db_code(1045, Some("28000")), None);
+/// ```
+///
+/// `from_db_code` is documented, for crates which deny `missing_docs`:
+/// ```
+/// #![deny(missing_docs)]
+/// //! Errors of the repository.
+///
+/// use web_proc_macros::DbError;
+///
+/// /// Errors of the queries.
+/// #[derive(DbError)]
+/// pub enum QueryError {
+///     /// The row already exists.
+///     #[db_error(mysql = 1062)]
+///     Duplicated,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Each code can be used by just one variant:
+/// ```compile_fail
+/// use web_proc_macros::DbError;
+///
+/// #[derive(DbError)]
+/// enum QueryError {
+///     #[db_error(mysql = 1062)]
+///     Duplicated,
+///
+///     #[db_error(mysql = 1062)]
+///     Conflict,
+/// }
+/// ```
+#[proc_macro_derive(DbError, attributes(db_error))]
+pub fn db_error(input: TokenStream) -> TokenStream {
+    db_error_macro::db_error_macro(input)
+}
+
 /// Use insert_stmt_query!(TABLE_NAME, COLUMS_LIST...)
 /// # Examples
 /// ```
 /// use web_proc_macros::insert_stmt_query;
 ///
 /// let query = insert_stmt_query!("table", "col1", "col2");
+/// # #[cfg(not(feature = "location-comments"))]
 /// assert_eq!(query, "INSERT INTO table (col1, col2) VALUES (:col1, :col2)");
 /// ```
+///
+/// `#[tenant]` adds the `tenant_id` column, or the column set with `#[tenant = "..."]`:
+/// ```
+/// use web_proc_macros::insert_stmt_query;
+///
+/// let query = insert_stmt_query!(#[tenant = "org_id"] "table", "col1");
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(query, "INSERT INTO table (col1, org_id) VALUES (:col1, :org_id)");
+/// ```
 #[proc_macro]
 pub fn insert_stmt_query(input: TokenStream) -> TokenStream {
     insert_macro::insert_stmt_macro(input)
@@ -91,6 +1420,7 @@ pub fn INSERT(input: TokenStream) -> TokenStream {
 ///
 /// let where_clause = "id = :id";
 /// let query = select_stmt_query!("table", "col1", "col2", where_clause);
+/// # #[cfg(not(feature = "location-comments"))]
 /// assert_eq!(query, "SELECT col1, col2 FROM table WHERE id = :id");
 /// ```
 ///
@@ -98,14 +1428,76 @@ pub fn INSERT(input: TokenStream) -> TokenStream {
 /// use web_proc_macros::select_stmt_query;
 ///
 /// let query = select_stmt_query!("table", "*", "id = :id");
+/// # #[cfg(not(feature = "location-comments"))]
 /// assert_eq!(query, "SELECT * FROM table WHERE id = :id");
 /// ```
 /// ```
 /// use web_proc_macros::select_stmt_query;
 ///
 /// let query = select_stmt_query!("table", "*");
+/// # #[cfg(not(feature = "location-comments"))]
 /// assert_eq!(query, "SELECT * FROM table");
 /// ```
+///
+/// `#[tenant]` adds the tenant condition to the WHERE clause:
+/// ```
+/// use web_proc_macros::select_stmt_query;
+///
+/// let query = select_stmt_query!(#[tenant] "table", "*", "id = :id OR alias = :alias");
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(
+///     query,
+///     "SELECT * FROM table WHERE (id = :id OR alias = :alias) AND tenant_id = :tenant_id"
+/// );
+///
+/// let query = select_stmt_query!(#[tenant] "table", "*");
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(query, "SELECT * FROM table WHERE tenant_id = :tenant_id");
+/// ```
+///
+/// With the `location-comments` feature the query starts with the module, file
+/// and line of the macro call:
+/// ```
+/// use web_proc_macros::select_stmt_query;
+///
+/// let (query, line) = (select_stmt_query!("table", "*", "id = :id"), line!());
+/// # #[cfg(feature = "location-comments")]
+/// assert_eq!(
+///     query,
+///     format!(
+///         "/* {} {}:{line} */ SELECT * FROM table WHERE id = :id",
+///         module_path!(),
+///         file!(),
+///     )
+/// );
+/// ```
+///
+/// The literal table and columns are checked against the schema file, this crate
+/// sets `tests/fixtures/schema.sql` in its `[package.metadata.web_proc_macros]`:
+/// ```
+/// use web_proc_macros::select_stmt_query;
+///
+/// let query = select_stmt_query!("posts", "id", "created_at", "author = :author");
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(query, "SELECT id, created_at FROM posts WHERE author = :author");
+///
+/// // Columns after a `DEFAULT ';'` are still read
+/// let query = select_stmt_query!("table", "separator", "alias", "id = :id");
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(query, "SELECT separator, alias FROM table WHERE id = :id");
+/// ```
+///
+/// ```compile_fail
+/// use web_proc_macros::select_stmt_query;
+///
+/// let query = select_stmt_query!("posts", "id", "title", "id = :id");
+/// ```
+///
+/// ```compile_fail
+/// use web_proc_macros::select_stmt_query;
+///
+/// let query = select_stmt_query!("articles", "id", "id = :id");
+/// ```
 #[proc_macro]
 pub fn select_stmt_query(input: TokenStream) -> TokenStream {
     select_macro::select_stmt_macro(input)
@@ -118,12 +1510,74 @@ pub fn SELECT(input: TokenStream) -> TokenStream {
     select_macro::select_stmt_macro(input)
 }
 
+/// Use union_stmt_query!(SELECT, OPERATOR SELECT..., ORDER BY CLAUSE, LIMIT CLAUSE)
+///
+/// The operators are `UNION`, `UNION ALL`, `INTERSECT` and `EXCEPT`, `ORDER BY` and
+/// `LIMIT` are optional and apply to the combined result. The selects must have the
+/// same number of columns, which is checked when they are literals.
+///
+/// Merging the parameters of the selects is out of scope, the query just keeps their
+/// placeholders. A name used in several selects, like `:author` below, is one
+/// parameter for drivers which bind named parameters by name, but drivers which
+/// bind them by position need a value for each occurrence.
+/// # Examples
+/// ```
+/// use web_proc_macros::{select_stmt_query, union_stmt_query};
+///
+/// let query = union_stmt_query!(
+///     select_stmt_query!("posts", "id", "created_at", "author = :author"),
+///     UNION ALL select_stmt_query!("comments", "id", "created_at", "author = :author"),
+///     ORDER BY "created_at DESC",
+///     LIMIT 20,
+/// );
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(
+///     query,
+///     "SELECT id, created_at FROM posts WHERE author = :author \
+///     UNION ALL SELECT id, created_at FROM comments WHERE author = :author \
+///     ORDER BY created_at DESC LIMIT 20"
+/// );
+///
+/// // The selects can be invoked by their path
+/// let query = union_stmt_query!(
+///     web_proc_macros::select_stmt_query!("posts", "id", "author = :author"),
+///     EXCEPT web_proc_macros::SELECT!("comments", "id", "author = :author"),
+/// );
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(
+///     query,
+///     "SELECT id FROM posts WHERE author = :author \
+///     EXCEPT SELECT id FROM comments WHERE author = :author"
+/// );
+/// ```
+///
+/// ```compile_fail
+/// use web_proc_macros::{select_stmt_query, union_stmt_query};
+///
+/// let query = union_stmt_query!(
+///     select_stmt_query!("posts", "id", "created_at", "author = :author"),
+///     UNION select_stmt_query!("comments", "id", "author = :author"),
+/// );
+/// ```
+#[proc_macro]
+pub fn union_stmt_query(input: TokenStream) -> TokenStream {
+    union_macro::union_stmt_macro(input)
+}
+
+/// Alias to [union_stmt_query]
+#[allow(non_snake_case)]
+#[proc_macro]
+pub fn UNION(input: TokenStream) -> TokenStream {
+    union_macro::union_stmt_macro(input)
+}
+
 /// Use update_stmt_query!(TABLE_NAME, COLUMS_LIST..., WHERE_CLAUSE)
 /// # Examples
 /// ```
 /// use web_proc_macros::update_stmt_query;
 ///
 /// let query = update_stmt_query!("table", "col1", "col2", "id = :id");
+/// # #[cfg(not(feature = "location-comments"))]
 /// assert_eq!(query, "UPDATE table SET col1 = :col1, col2 = :col2 WHERE id = :id");
 /// ```
 #[proc_macro]
@@ -144,6 +1598,19 @@ pub fn UPDATE(input: TokenStream) -> TokenStream {
 /// use web_proc_macros::delete_stmt_query;
 ///
 /// let query = delete_stmt_query!("table", "id = :id");
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(query, "DELETE FROM table WHERE id = :id");
+/// ```
+///
+/// ```
+/// use web_proc_macros::delete_stmt_query;
+///
+/// let query = delete_stmt_query!(#[tenant] "table", "id = :id");
+/// # #[cfg(not(feature = "location-comments"))]
+/// assert_eq!(query, "DELETE FROM table WHERE (id = :id) AND tenant_id = :tenant_id");
+///
+/// let query = delete_stmt_query!(#[unscoped] "table", "id = :id");
+/// # #[cfg(not(feature = "location-comments"))]
 /// assert_eq!(query, "DELETE FROM table WHERE id = :id");
 /// ```
 #[proc_macro]
@@ -580,3 +2047,98 @@ pub fn derive_partial_object(input: TokenStream) -> TokenStream {
 pub fn derive_partial_struct(input: TokenStream) -> TokenStream {
     partial_struct::partial_struct(input)
 }
+
+/// Generates a `CREATE_TABLE_SQL` const with the DDL of the struct.
+///
+/// The table name is the snake_case name of the struct, and the dialect
+/// is `mysql` by default. Both can be set with
+/// `#[sql(table = "...", dialect = "postgres")]`. `Option<T>` fields are
+/// nullable columns.
+///
+/// Fields accept `#[sql(ty = "...")]`, `primary_key`, `unique`, `index` and `skip`.
+///
+/// `#[sql(tenant)]` or `#[sql(tenant = "...")]` marks a tenant table: the tenant
+/// column is indexed, and added with the `#[sql(tenant_ty = "...")]` type when
+/// the struct doesn't have it.
+///
+/// # Examples
+/// ```
+/// use web_proc_macros::CreateTable;
+///
+/// #[derive(CreateTable)]
+/// #[sql(table = "users")]
+/// pub struct User {
+///     #[sql(primary_key)]
+///     id: String,
+///     #[sql(ty = "VARCHAR(64)", unique)]
+///     name: String,
+///     #[sql(index)]
+///     status: u8,
+///     avatar: Option<Vec<u8>>,
+/// }
+///
+/// assert_eq!(
+///     User::CREATE_TABLE_SQL,
+///     "CREATE TABLE users (id VARCHAR(255) NOT NULL, name VARCHAR(64) NOT NULL UNIQUE, \
+///     status TINYINT UNSIGNED NOT NULL, avatar BLOB, PRIMARY KEY (id));\n\
+///     CREATE INDEX users_status_idx ON users (status);"
+/// );
+/// ```
+///
+/// ```
+/// use web_proc_macros::CreateTable;
+///
+/// #[derive(CreateTable)]
+/// #[sql(dialect = "postgres")]
+/// pub struct AuditLog {
+///     id: i64,
+///     is_active: bool,
+/// }
+///
+/// assert_eq!(
+///     AuditLog::CREATE_TABLE_SQL,
+///     "CREATE TABLE audit_log (id BIGINT NOT NULL, is_active BOOLEAN NOT NULL);"
+/// );
+/// ```
+///
+/// ```
+/// use web_proc_macros::CreateTable;
+///
+/// #[derive(CreateTable)]
+/// #[sql(table = "invoices", tenant, tenant_ty = "BIGINT UNSIGNED")]
+/// pub struct Invoice {
+///     #[sql(primary_key)]
+///     id: u64,
+///     total: f64,
+/// }
+///
+/// assert_eq!(
+///     Invoice::CREATE_TABLE_SQL,
+///     "CREATE TABLE invoices (id BIGINT UNSIGNED NOT NULL, total DOUBLE NOT NULL, \
+///     tenant_id BIGINT UNSIGNED NOT NULL, PRIMARY KEY (id));\n\
+///     CREATE INDEX invoices_tenant_id_idx ON invoices (tenant_id);"
+/// );
+/// ```
+///
+/// A skipped field doesn't count as the tenant column:
+/// ```
+/// use web_proc_macros::CreateTable;
+///
+/// #[derive(CreateTable)]
+/// #[sql(tenant, tenant_ty = "BIGINT UNSIGNED")]
+/// pub struct Payment {
+///     id: u64,
+///     #[sql(skip)]
+///     tenant_id: u64,
+/// }
+///
+/// assert_eq!(
+///     Payment::CREATE_TABLE_SQL,
+///     "CREATE TABLE payment (id BIGINT UNSIGNED NOT NULL, tenant_id BIGINT UNSIGNED NOT NULL);\n\
+///     CREATE INDEX payment_tenant_id_idx ON payment (tenant_id);"
+/// );
+/// ```
+#[proc_macro_derive(CreateTable, attributes(sql))]
+pub fn derive_create_table(input: TokenStream) -> TokenStream {
+    create_table_macro::create_table_macro(input)
+}
@@ -0,0 +1,22 @@
+use proc_macro2::TokenStream;
+#[cfg(feature = "location-comments")]
+use quote::quote;
+
+/// Prepends a `/* module::path file:line */` comment to the query built by
+/// the stmt macros. `module_path!`, `file!` and `line!` are resolved with the
+/// call site span, so they point to the macro invocation.
+#[cfg(feature = "location-comments")]
+pub fn with_location(query: TokenStream) -> TokenStream {
+    quote! {
+        format!(
+            "{}{}",
+            concat!("/* ", module_path!(), " ", file!(), ":", line!(), " */ "),
+            #query,
+        )
+    }
+}
+
+#[cfg(not(feature = "location-comments"))]
+pub fn with_location(query: TokenStream) -> TokenStream {
+    query
+}
@@ -0,0 +1,288 @@
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Error, Expr, ExprLit, Lit, LitStr, Result};
+
+use crate::config::{load_cached, manifest_dir, setting, FileCache};
+
+pub const SCHEMA_VAR: &str = "WEB_PROC_MACROS_SCHEMA";
+
+const CONSTRAINT_KEYWORDS: [&str; 9] = [
+    "PRIMARY",
+    "UNIQUE",
+    "KEY",
+    "INDEX",
+    "CONSTRAINT",
+    "FOREIGN",
+    "CHECK",
+    "FULLTEXT",
+    "SPATIAL",
+];
+
+thread_local! {
+    static SCHEMAS: FileCache<Schema> = RefCell::new(HashMap::new());
+}
+
+/// Tables and their columns, parsed from the `CREATE TABLE` statements of the schema file.
+struct Schema {
+    tables: Vec<(String, Vec<String>)>,
+}
+
+impl Schema {
+    fn parse(sql: &str) -> Self {
+        let sql = strip_comments(sql);
+        let mut tables = Vec::new();
+        for statement in split_statements(&sql) {
+            let Some(open) = statement.find('(') else {
+                continue;
+            };
+
+            let head: Vec<_> = statement[..open].split_whitespace().collect();
+            let is_create_table = head
+                .first()
+                .is_some_and(|w| w.eq_ignore_ascii_case("CREATE"))
+                && head.iter().any(|w| w.eq_ignore_ascii_case("TABLE"));
+            if !is_create_table {
+                continue;
+            }
+
+            let name = unquote(head.last().unwrap().rsplit('.').next().unwrap());
+            let columns = split_definitions(&statement[open + 1..])
+                .into_iter()
+                .filter_map(|definition| {
+                    let first = definition.split_whitespace().next()?;
+                    if CONSTRAINT_KEYWORDS
+                        .iter()
+                        .any(|k| first.eq_ignore_ascii_case(k))
+                    {
+                        None
+                    } else {
+                        Some(unquote(first))
+                    }
+                })
+                .collect();
+
+            tables.push((name, columns));
+        }
+
+        Schema { tables }
+    }
+
+    fn load(path: &PathBuf) -> std::io::Result<Rc<Schema>> {
+        load_cached(&SCHEMAS, path, Schema::parse)
+    }
+
+    fn table(&self, name: &str) -> Option<&[String]> {
+        self.tables
+            .iter()
+            .find(|(table, _)| table.eq_ignore_ascii_case(name))
+            .map(|(_, columns)| columns.as_slice())
+    }
+}
+
+/// Verifies the literal table and columns of a stmt macro against the schema
+/// file set in `WEB_PROC_MACROS_SCHEMA` or in the `schema` key of
+/// `[package.metadata.web_proc_macros]`.
+///
+/// Returns the tokens which make the compiler track the schema file, so queries
+/// are checked again when it changes. The stmt macros track [SCHEMA_VAR] and the
+/// manifest along with the other settings.
+pub fn check_columns(table: &Expr, cols: &[LitStr]) -> Result<TokenStream> {
+    let Some(schema_file) = setting(SCHEMA_VAR, "schema") else {
+        return Ok(TokenStream::new());
+    };
+
+    let path = manifest_dir().join(&schema_file);
+    let schema = Schema::load(&path).map_err(|err| {
+        Error::new(
+            Span::call_site(),
+            format!("Error reading schema file `{}`: {err}", path.display()),
+        )
+    })?;
+
+    let path_str = path.to_string_lossy().into_owned();
+    let tracking = quote! {
+        const _: &[u8] = include_bytes!(#path_str);
+    };
+
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(table),
+        ..
+    }) = table
+    else {
+        return Ok(tracking);
+    };
+
+    let table_name = table.value();
+    let table_name = table_name.rsplit('.').next().unwrap();
+    let Some(columns) = schema.table(table_name) else {
+        let tables: Vec<_> = schema
+            .tables
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        return Err(Error::new(
+            table.span(),
+            unknown_message("table", table_name, &schema_file, &tables),
+        ));
+    };
+
+    let mut errors: Option<Error> = None;
+    for col in cols {
+        let col_name = col.value();
+        let col_name = col_name.rsplit('.').next().unwrap();
+        if !is_identifier(col_name) || columns.iter().any(|c| c.eq_ignore_ascii_case(col_name)) {
+            continue;
+        }
+
+        let columns: Vec<_> = columns.iter().map(String::as_str).collect();
+        let error = Error::new(
+            col.span(),
+            unknown_message(
+                "column",
+                col_name,
+                &format!("table `{table_name}`"),
+                &columns,
+            ),
+        );
+        match &mut errors {
+            Some(errors) => errors.combine(error),
+            None => errors = Some(error),
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(tracking),
+    }
+}
+
+fn unknown_message(what: &str, name: &str, location: &str, candidates: &[&str]) -> String {
+    let mut message = format!("unknown {what} `{name}` in {location}");
+    if let Some(suggestion) = did_you_mean(name, candidates) {
+        message.push_str(&format!(", did you mean `{suggestion}`?"));
+    }
+
+    message
+}
+
+fn did_you_mean<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    candidates
+        .iter()
+        .map(|candidate| (levenshtein(&name, &candidate.to_lowercase()), *candidate))
+        .filter(|(distance, _)| *distance <= (name.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            current.push(substitution.min(prev[j + 1] + 1).min(current[j] + 1));
+        }
+        prev = current;
+    }
+
+    prev[b.len()]
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn unquote(name: &str) -> String {
+    name.trim_matches(|c| matches!(c, '`' | '"' | '[' | ']'))
+        .to_string()
+}
+
+fn strip_comments(sql: &str) -> String {
+    let mut stripped = String::with_capacity(sql.len());
+    let mut rest = sql;
+    while !rest.is_empty() {
+        if let Some(len) = quoted_len(rest) {
+            stripped.push_str(&rest[..len]);
+            rest = &rest[len..];
+        } else if let Some(after) = rest.strip_prefix("--") {
+            rest = after.find('\n').map_or("", |end| &after[end..]);
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.find("*/").map_or("", |end| &after[end + 2..]);
+        } else {
+            let c = rest.chars().next().unwrap();
+            stripped.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    stripped
+}
+
+/// Length of the quoted string or identifier at the start of `sql`, up to the
+/// end when it isn't closed. Doubled quotes (`'it''s'`) are two strings in a row.
+fn quoted_len(sql: &str) -> Option<usize> {
+    let quote = sql
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '\'' | '"' | '`'))?;
+    Some(sql[1..].find(quote).map_or(sql.len(), |end| end + 2))
+}
+
+/// Splits the statements of the schema by the semicolons outside quoted text.
+fn split_statements(sql: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < sql.len() {
+        if let Some(len) = quoted_len(&sql[i..]) {
+            i += len;
+            continue;
+        }
+
+        let c = sql[i..].chars().next().unwrap();
+        if c == ';' {
+            statements.push(&sql[start..i]);
+            start = i + 1;
+        }
+        i += c.len_utf8();
+    }
+
+    statements.push(&sql[start..]);
+    statements
+}
+
+/// Splits the body of a `CREATE TABLE` by the top level commas, up to the closing parenthesis.
+fn split_definitions(body: &str) -> Vec<&str> {
+    let mut definitions = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        match c {
+            '\'' => in_string = !in_string,
+            _ if in_string => {}
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                definitions.push(&body[start..i]);
+                return definitions;
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                definitions.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    definitions.push(&body[start..]);
+    definitions
+}
@@ -5,7 +5,15 @@ use syn::{
     parse_macro_input, Expr, LitStr, Result,
 };
 
+use crate::{
+    config::setting_tracking,
+    location_comment::with_location,
+    schema::{check_columns, SCHEMA_VAR},
+    tenant::{TenantScope, TENANT_COLUMN_VAR},
+};
+
 pub struct SelectUpdateQueryInput {
+    pub tenant: TenantScope,
     pub table_name: Expr,
     pub where_clause: Option<Expr>,
     pub cols: Vec<LitStr>,
@@ -13,6 +21,7 @@ pub struct SelectUpdateQueryInput {
 
 impl Parse for SelectUpdateQueryInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        let tenant = TenantScope::parse(input)?;
         let table_name = input.parse()?;
         input.parse::<syn::Token![,]>()?;
 
@@ -30,6 +39,7 @@ impl Parse for SelectUpdateQueryInput {
         }
 
         Ok(SelectUpdateQueryInput {
+            tenant,
             table_name,
             cols,
             where_clause,
@@ -40,12 +50,17 @@ impl Parse for SelectUpdateQueryInput {
 pub fn select_stmt_macro(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as SelectUpdateQueryInput);
     let table_name = &input.table_name;
-    let where_clause = &input.where_clause;
+    let where_clause = input.tenant.scope_where(input.where_clause.as_ref());
     let cols = &input.cols;
 
     let colums: Vec<_> = cols.iter().map(|col| col.value()).collect();
     let colums = colums.join(", ");
 
+    let schema_tracking = match check_columns(table_name, cols) {
+        Ok(tracking) => tracking,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let expanded = match where_clause {
         Some(clause) => quote! {
             format!(
@@ -64,5 +79,14 @@ pub fn select_stmt_macro(input: TokenStream) -> TokenStream {
         },
     };
 
-    TokenStream::from(expanded)
+    let expanded = with_location(expanded);
+    let settings_tracking = setting_tracking(&[SCHEMA_VAR, TENANT_COLUMN_VAR]);
+
+    TokenStream::from(quote! {
+        {
+            #settings_tracking
+            #schema_tracking
+            #expanded
+        }
+    })
 }
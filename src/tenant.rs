@@ -0,0 +1,85 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse::ParseStream, Attribute, Error, Expr, Lit, Meta, Result};
+
+use crate::config::{setting, setting_tracking};
+
+pub const DEFAULT_TENANT_COLUMN: &str = "tenant_id";
+
+pub const TENANT_COLUMN_VAR: &str = "WEB_PROC_MACROS_TENANT_COLUMN";
+
+/// Tenant column set in `WEB_PROC_MACROS_TENANT_COLUMN` or in the `tenant_column`
+/// key of `[package.metadata.web_proc_macros]`. When it's set, every query
+/// is scoped unless it's marked as `unscoped`.
+pub fn default_tenant_column() -> Option<String> {
+    setting(TENANT_COLUMN_VAR, "tenant_column")
+}
+
+/// Tracks the settings of the default tenant column, so the macros which read it
+/// run again when it changes.
+pub fn tenant_tracking() -> TokenStream {
+    setting_tracking(&[TENANT_COLUMN_VAR])
+}
+
+/// Resolves the tenant column from the `tenant`/`unscoped` markers, `scoped`
+/// is `None` when there is no marker.
+pub fn tenant_column(scoped: Option<bool>, column: Option<String>) -> Option<String> {
+    match scoped {
+        Some(false) => None,
+        Some(true) => column
+            .or_else(default_tenant_column)
+            .or_else(|| Some(DEFAULT_TENANT_COLUMN.to_string())),
+        None => default_tenant_column(),
+    }
+}
+
+/// Tenant scoping of a stmt macro, set with `#[tenant]`, `#[tenant = "column"]`
+/// or `#[unscoped]` before the table name.
+pub struct TenantScope {
+    pub column: Option<String>,
+}
+
+impl TenantScope {
+    pub fn parse(input: ParseStream) -> Result<Self> {
+        let mut scoped = None;
+        let mut column = None;
+        for attr in input.call(Attribute::parse_outer)? {
+            match attr.parse_meta()? {
+                Meta::Path(path) if path.is_ident("tenant") => scoped = Some(true),
+                Meta::Path(path) if path.is_ident("unscoped") => scoped = Some(false),
+                Meta::NameValue(nv) if nv.path.is_ident("tenant") => match nv.lit {
+                    Lit::Str(lit) => {
+                        scoped = Some(true);
+                        column = Some(lit.value());
+                    }
+                    lit => return Err(Error::new_spanned(lit, "expected a column name")),
+                },
+                meta => {
+                    return Err(Error::new_spanned(
+                        meta,
+                        "expected `#[tenant]`, `#[tenant = \"column\"]` or `#[unscoped]`",
+                    ))
+                }
+            }
+        }
+
+        Ok(TenantScope {
+            column: tenant_column(scoped, column),
+        })
+    }
+
+    /// Adds the tenant condition to the WHERE clause given to the macro.
+    pub fn scope_where(&self, where_clause: Option<&Expr>) -> Option<TokenStream> {
+        let Some(column) = &self.column else {
+            return where_clause.map(|clause| quote!(#clause));
+        };
+
+        let condition = format!("{column} = :{column}");
+        match where_clause {
+            Some(clause) => Some(quote! {
+                format!("({}) AND {}", #clause, #condition)
+            }),
+            None => Some(quote!(#condition)),
+        }
+    }
+}
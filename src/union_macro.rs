@@ -0,0 +1,158 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Expr, ExprMacro, Ident, Result,
+};
+
+use crate::select_macro::SelectUpdateQueryInput;
+
+struct UnionQueryInput {
+    first: ExprMacro,
+    rest: Vec<(String, ExprMacro)>,
+    order_by: Option<Expr>,
+    limit: Option<Expr>,
+}
+
+impl Parse for UnionQueryInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let first = parse_select(input)?;
+        let mut rest = Vec::new();
+        let mut order_by = None;
+        let mut limit = None;
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let keyword: Ident = input.parse()?;
+            match keyword.to_string().as_str() {
+                "UNION" | "INTERSECT" | "EXCEPT" if order_by.is_none() && limit.is_none() => {
+                    let mut operator = keyword.to_string();
+                    if input.fork().parse::<Ident>().is_ok_and(|all| all == "ALL") {
+                        input.parse::<Ident>()?;
+                        operator.push_str(" ALL");
+                    }
+                    rest.push((operator, parse_select(input)?));
+                }
+                "ORDER" if limit.is_none() => {
+                    let by: Ident = input.parse()?;
+                    if by != "BY" {
+                        return Err(Error::new(by.span(), "expected `ORDER BY`"));
+                    }
+                    order_by = Some(input.parse()?);
+                }
+                "LIMIT" => limit = Some(input.parse()?),
+                _ => {
+                    return Err(Error::new(
+                        keyword.span(),
+                        "expected `UNION`, `UNION ALL`, `INTERSECT`, `EXCEPT`, then `ORDER BY` and `LIMIT`",
+                    ))
+                }
+            }
+        }
+
+        if rest.is_empty() {
+            return Err(input.error("union_stmt_query requires at least two selects"));
+        }
+
+        Ok(UnionQueryInput {
+            first,
+            rest,
+            order_by,
+            limit,
+        })
+    }
+}
+
+fn parse_select(input: ParseStream) -> Result<ExprMacro> {
+    let select: ExprMacro = input.parse()?;
+    let path = &select.mac.path;
+    let name = path
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string());
+    if !matches!(name.as_deref(), Some("select_stmt_query" | "SELECT")) {
+        return Err(Error::new_spanned(
+            path,
+            "expected a `select_stmt_query!` invocation",
+        ));
+    }
+
+    Ok(select)
+}
+
+/// Returns the number of columns of the select, or `None` when it
+/// depends on the table (`*`).
+fn column_count(select: &ExprMacro) -> Result<Option<usize>> {
+    let input: SelectUpdateQueryInput = select.mac.parse_body()?;
+    let mut count = 0;
+    for col in &input.cols {
+        let col = col.value();
+        let mut depth = 0;
+        for part in col.split(',') {
+            if depth == 0 {
+                let part = part.trim();
+                if part == "*" || part.ends_with(".*") {
+                    return Ok(None);
+                }
+                count += 1;
+            }
+            depth += part.matches('(').count() as i32 - part.matches(')').count() as i32;
+        }
+    }
+
+    Ok(Some(count))
+}
+
+pub fn union_stmt_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as UnionQueryInput);
+    let first = &input.first;
+    let selects: Vec<_> = input.rest.iter().map(|(_, select)| select).collect();
+
+    let expected = match column_count(first) {
+        Ok(count) => count,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    for select in &selects {
+        match (expected, column_count(select)) {
+            (_, Err(err)) => return err.to_compile_error().into(),
+            (Some(expected), Ok(Some(count))) if count != expected => {
+                return Error::new_spanned(
+                    select,
+                    format!("select has {count} columns, but the first select has {expected}"),
+                )
+                .to_compile_error()
+                .into();
+            }
+            _ => {}
+        }
+    }
+
+    let mut query = String::from("{}");
+    for (operator, _) in &input.rest {
+        query.push_str(&format!(" {operator} {{}}"));
+    }
+
+    let order_by = input.order_by.iter();
+    if input.order_by.is_some() {
+        query.push_str(" ORDER BY {}");
+    }
+    let limit = input.limit.iter();
+    if input.limit.is_some() {
+        query.push_str(" LIMIT {}");
+    }
+
+    let expanded = quote! {
+        format!(
+            #query,
+            #first,
+            #(#selects,)*
+            #(#order_by,)*
+            #(#limit,)*
+        )
+    };
+
+    TokenStream::from(expanded)
+}
@@ -2,12 +2,18 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::parse_macro_input;
 
-use crate::select_macro::SelectUpdateQueryInput;
+use crate::{
+    config::setting_tracking,
+    location_comment::with_location,
+    schema::{check_columns, SCHEMA_VAR},
+    select_macro::SelectUpdateQueryInput,
+    tenant::TENANT_COLUMN_VAR,
+};
 
 pub fn update_stmt_macro(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as SelectUpdateQueryInput);
     let table_name = &input.table_name;
-    let where_clause = &input.where_clause;
+    let where_clause = input.tenant.scope_where(input.where_clause.as_ref());
     let cols = &input.cols;
 
     let col_pairs: Vec<_> = cols
@@ -16,6 +22,11 @@ pub fn update_stmt_macro(input: TokenStream) -> TokenStream {
         .collect();
     let col_pairs = col_pairs.join(", ");
 
+    let schema_tracking = match check_columns(table_name, cols) {
+        Ok(tracking) => tracking,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let expanded = quote! {
         format!(
             "UPDATE {} SET {} WHERE {}",
@@ -25,5 +36,14 @@ pub fn update_stmt_macro(input: TokenStream) -> TokenStream {
         )
     };
 
-    TokenStream::from(expanded)
+    let expanded = with_location(expanded);
+    let settings_tracking = setting_tracking(&[SCHEMA_VAR, TENANT_COLUMN_VAR]);
+
+    TokenStream::from(quote! {
+        {
+            #settings_tracking
+            #schema_tracking
+            #expanded
+        }
+    })
 }
@@ -0,0 +1,27 @@
+-- Schema the doctests of the stmt macros are checked against.
+CREATE TABLE `table` (
+    id BIGINT NOT NULL AUTO_INCREMENT,
+    separator VARCHAR(1) NOT NULL DEFAULT ';',
+    col1 VARCHAR(255) NOT NULL,
+    col2 VARCHAR(255) NOT NULL,
+    alias VARCHAR(255),
+    org_id BIGINT,
+    tenant_id BIGINT,
+    PRIMARY KEY (id)
+);
+
+INSERT INTO `table` (separator, col1, col2) VALUES (';', 'it''s; not a statement', '--');
+
+CREATE TABLE posts (
+    id BIGINT NOT NULL,
+    author BIGINT NOT NULL,
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (id)
+);
+
+CREATE TABLE comments (
+    id BIGINT NOT NULL,
+    author BIGINT NOT NULL,
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (id)
+);
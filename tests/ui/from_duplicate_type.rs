@@ -0,0 +1,26 @@
+use web_proc_macros::ErrorKind;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ErrorType {
+    Invalid,
+}
+
+#[derive(Debug, ErrorKind)]
+#[error_kind(ErrorType)]
+enum CacheError {
+    #[error_kind(ErrorType, Invalid)]
+    Missing,
+}
+
+// `Box<CacheError>` also generates `From<CacheError>`
+#[derive(Debug, ErrorKind)]
+#[error_kind(ErrorType)]
+enum ServiceError {
+    #[error_kind(transparent, from)]
+    Cache(CacheError),
+
+    #[error_kind(transparent, from)]
+    Session(Box<CacheError>),
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+use web_proc_macros::ErrorKind;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum DbErrorKind {
+    Duplicated,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ServiceErrorKind {
+    Conflict,
+}
+
+#[derive(ErrorKind)]
+#[error_kind(DbErrorKind)]
+enum DbError {
+    #[error_kind(DbErrorKind, Duplicated)]
+    Duplicated,
+}
+
+#[derive(ErrorKind)]
+#[error_kind(ServiceErrorKind)]
+enum ServiceError {
+    #[error_kind(transparent)]
+    Db(DbError),
+
+    #[error_kind(ServiceErrorKind, Conflict)]
+    Conflict,
+}
+
+fn main() {}